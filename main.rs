@@ -1,63 +1,343 @@
-use std::collections::HashMap;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::env;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 // Constants
 const PI: f64 = 3.14159;
+const UNIT_CIRCLE_AREA: f64 = circle_area_const(1.0);
 
 fn main() {
     println!("--- Welcome to the Full Rust Demo ---");
 
-    // Section 1: Ownership and Borrowing
-    ownership_and_borrowing();
+    let panics = install_panic_hook();
+    install_ctrl_c_listener();
+    let results = run_sections(all_sections(), &shutdown_signal().subscribe());
 
-    // Section 2: Generics and Traits
-    generics_and_traits();
+    println!("\n--- Summary ---");
+    print!("{}", format_summary(&results));
+    println!("Panics recorded: {:?}", lock_recover(&panics));
 
-    // Section 3: Enums and Pattern Matching
-    enums_and_pattern_matching();
+    let code = if *shutdown_signal().borrow() {
+        INTERRUPTED_EXIT_CODE
+    } else {
+        exit_code(&results)
+    };
+    if code != 0 {
+        std::process::exit(code);
+    }
+}
+
+type Section = (&'static str, fn() -> SectionResult);
+
+// Sections run in this order; add new ones here.
+fn all_sections() -> Vec<Section> {
+    vec![
+        ("Ownership and Borrowing", ownership_and_borrowing),
+        ("Generics and Traits", generics_and_traits),
+        ("Enums and Pattern Matching", enums_and_pattern_matching),
+        ("Error Handling", error_handling),
+        ("Iterators and Closures", iterators_and_closures),
+        ("Async Programming", async_runtime_demo),
+        ("Multithreading with Mutex", multithreading_with_mutex),
+        ("Smart Pointers", smart_pointers_demo),
+        ("Collections", collections_demo),
+        ("Macros", macros_demo),
+        ("Command-Line Arguments", command_line_demo),
+        ("Panic Recovery", panic_recovery_demo),
+    ]
+}
+
+// Section outcomes, collected by `main` into a summary table
+struct SectionResult {
+    name: String,
+    success: bool,
+    detail: String,
+}
+
+impl SectionResult {
+    fn ok(name: &str, detail: impl Into<String>) -> Self {
+        SectionResult {
+            name: name.to_string(),
+            success: true,
+            detail: detail.into(),
+        }
+    }
+
+    fn failed(name: &str, detail: impl Into<String>) -> Self {
+        SectionResult {
+            name: name.to_string(),
+            success: false,
+            detail: detail.into(),
+        }
+    }
+}
+
+// Stops before the next section once shutdown has been requested.
+fn run_sections(
+    sections: Vec<Section>,
+    shutdown: &tokio::sync::watch::Receiver<bool>,
+) -> Vec<SectionResult> {
+    let mut results = Vec::new();
+    for (name, section) in sections {
+        if *shutdown.borrow() {
+            println!("Shutdown requested, skipping the remaining sections");
+            break;
+        }
+        results.push(run_section(name, section));
+    }
+    results
+}
+
+fn run_section(
+    name: &str,
+    f: impl FnOnce() -> SectionResult + std::panic::UnwindSafe,
+) -> SectionResult {
+    match std::panic::catch_unwind(f) {
+        Ok(result) => result,
+        Err(payload) => {
+            SectionResult::failed(name, format!("panicked: {}", panic_message(&*payload)))
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|msg| msg.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| String::from("unknown panic"))
+}
+
+// Records every panic message while still running the previously installed hook.
+fn install_panic_hook() -> Arc<Mutex<Vec<String>>> {
+    let recorded = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&recorded);
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        lock_recover(&sink).push(panic_message(info.payload()));
+        previous(info);
+    }));
+    recorded
+}
+
+// 128 + SIGINT, the conventional status for a run ended by Ctrl-C.
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+// How long the current section gets to wind down after Ctrl-C.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
+
+// Process-wide shutdown flag, set once by the Ctrl-C listener.
+fn shutdown_signal() -> &'static tokio::sync::watch::Sender<bool> {
+    static SHUTDOWN: OnceLock<tokio::sync::watch::Sender<bool>> = OnceLock::new();
+    SHUTDOWN.get_or_init(|| tokio::sync::watch::channel(false).0)
+}
+
+// Owns SIGINT for the whole process. tokio never restores the default
+// handler, so this must be the only `ctrl_c()` caller: it flags shutdown for
+// the running sections, then exits in case one is blocked (e.g. on stdin).
+fn install_ctrl_c_listener() {
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            println!("Ctrl-C handling unavailable: {}", e);
+            return;
+        }
+    };
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    thread::spawn(move || {
+        runtime.block_on(async {
+            let ctrl_c = tokio::signal::ctrl_c();
+            tokio::pin!(ctrl_c);
+            // The first poll registers the handler; only then may `main` go on.
+            let first = futures::poll!(&mut ctrl_c);
+            let _ = ready_tx.send(());
+            let received = match first {
+                std::task::Poll::Ready(received) => received,
+                std::task::Poll::Pending => ctrl_c.await,
+            };
+            if received.is_ok() {
+                println!("\nCtrl-C received, shutting down...");
+                shutdown_signal().send_replace(true);
+                tokio::time::sleep(SHUTDOWN_GRACE).await;
+                std::process::exit(INTERRUPTED_EXIT_CODE);
+            }
+        });
+    });
+    let _ = ready_rx.recv();
+}
+
+fn exit_code(results: &[SectionResult]) -> i32 {
+    if results.iter().all(|result| result.success) {
+        0
+    } else {
+        1
+    }
+}
+
+fn format_summary(results: &[SectionResult]) -> String {
+    let width = results
+        .iter()
+        .map(|r| r.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("Section".len());
+
+    let mut table = format!("{:<width$}  {:<6}  {}\n", "Section", "Status", "Detail");
+    for result in results {
+        let status = if result.success { "OK" } else { "FAILED" };
+        table.push_str(&format!(
+            "{:<width$}  {:<6}  {}\n",
+            result.name, status, result.detail
+        ));
+    }
+    table
+}
+
+// Configuration, parsed from the environment exactly once
+struct Config {
+    verbose: bool,
+    async_io: bool,
+    stdin: bool,
+    repl: bool,
+    args: Vec<String>,
+}
+
+impl Config {
+    fn from_env() -> Self {
+        let (flags, args): (Vec<String>, Vec<String>) = env::args()
+            .skip(1)
+            .partition(|arg| arg == "--stdin" || arg == "--repl");
+        // Verbosity is opt-out: any value other than 0/false keeps it on.
+        let verbose = env::var("DEMO_VERBOSE")
+            .map(|value| value != "0" && !value.eq_ignore_ascii_case("false"))
+            .unwrap_or(true);
+        Config {
+            verbose,
+            async_io: env_flag("DEMO_ASYNC_IO", false),
+            stdin: flags.iter().any(|flag| flag == "--stdin"),
+            repl: flags.iter().any(|flag| flag == "--repl"),
+            args,
+        }
+    }
+}
+
+fn env_flag(name: &str, default: bool) -> bool {
+    match env::var(name) {
+        Ok(value) => value == "1" || value.eq_ignore_ascii_case("true"),
+        Err(_) => default,
+    }
+}
 
-    // Section 4: Error Handling
-    error_handling();
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SettingKey {
+    Verbose,
+    Threads,
+    Name,
+}
 
-    // Section 5: Iterators and Closures
-    iterators_and_closures();
+impl SettingKey {
+    fn default_value(self) -> &'static str {
+        match self {
+            SettingKey::Verbose => "true",
+            SettingKey::Threads => "4",
+            SettingKey::Name => "rust-demo",
+        }
+    }
+}
 
-    // Section 6: Async Programming
-    async_runtime_demo();
+struct Settings {
+    map: HashMap<SettingKey, String>,
+}
 
-    // Section 7: Multithreading with Mutex
-    multithreading_with_mutex();
+impl Settings {
+    fn new() -> Self {
+        Settings {
+            map: HashMap::new(),
+        }
+    }
 
-    // Section 8: Smart Pointers
-    smart_pointers_demo();
+    fn get(&self, key: SettingKey) -> &str {
+        self.map
+            .get(&key)
+            .map(String::as_str)
+            .unwrap_or_else(|| key.default_value())
+    }
 
-    // Section 9: Collections
-    collections_demo();
+    fn set(&mut self, key: SettingKey, value: impl Into<String>) {
+        self.map.insert(key, value.into());
+    }
+}
 
-    // Section 10: Macros
-    macros_demo();
+fn global_config() -> &'static Config {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+    init_once(&CONFIG, Config::from_env)
+}
 
-    // Section 11: Command-Line Arguments
-    command_line_demo();
+fn init_once(cell: &OnceLock<Config>, load: impl FnOnce() -> Config) -> &Config {
+    cell.get_or_init(load)
 }
 
 // Section 1: Ownership and Borrowing
-fn ownership_and_borrowing() {
+fn ownership_and_borrowing() -> SectionResult {
     println!("\n--- Ownership and Borrowing ---");
     let owned_string = String::from("I am owned!");
     let length = calculate_length(&owned_string);
     println!("Length of '{}' is {}", owned_string, length);
+
+    for input in ["already clean", "  Needs Work  "] {
+        match normalize(input) {
+            std::borrow::Cow::Borrowed(s) => println!("Borrowed unchanged: '{}'", s),
+            std::borrow::Cow::Owned(s) => println!("Owned normalized copy: '{}'", s),
+        }
+    }
+
+    for (start, end, token) in tokenize("borrow the  string slices") {
+        println!("Token '{}' at {}..{}", token, start, end);
+    }
+
+    SectionResult::ok("Ownership and Borrowing", format!("length {}", length))
 }
 
 fn calculate_length(s: &String) -> usize {
     s.len()
 }
 
+fn normalize(input: &str) -> std::borrow::Cow<'_, str> {
+    let trimmed = input.trim();
+    if trimmed.len() == input.len() && !input.chars().any(char::is_uppercase) {
+        std::borrow::Cow::Borrowed(input)
+    } else {
+        std::borrow::Cow::Owned(trimmed.to_lowercase())
+    }
+}
+
+fn tokenize(input: &str) -> Vec<(usize, usize, String)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (index, ch) in input.char_indices() {
+        match (ch.is_whitespace(), start) {
+            (true, Some(begin)) => {
+                tokens.push((begin, index, input[begin..index].to_string()));
+                start = None;
+            }
+            (false, None) => start = Some(index),
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        tokens.push((begin, input.len(), input[begin..].to_string()));
+    }
+    tokens
+}
+
 // Section 2: Generics and Traits
-fn generics_and_traits() {
+fn generics_and_traits() -> SectionResult {
     println!("\n--- Generics and Traits ---");
 
     let point = Point { x: 10, y: 20 };
@@ -65,15 +345,131 @@ fn generics_and_traits() {
 
     let circle = Circle { radius: 5.0 };
     println!("Circle area: {:.2}", circle.area());
+    println!("Unit circle area (const): {:.5}", UNIT_CIRCLE_AREA);
+    println!("Circle debug: {:?}", circle);
+
+    let (width, height) = circle.bounding_box();
+    println!("Circle bounding box: {:.2} x {:.2}", width, height);
+
+    let rectangle = Rectangle {
+        width: 4.0,
+        height: 3.0,
+    };
+    println!("Rectangle area: {:.2}", rectangle.area());
+    let (width, height) = rectangle.bounding_box();
+    println!("Rectangle bounding box: {:.2} x {:.2}", width, height);
+    println!("Circle circumference: {:.2}", circle.circumference());
+    let fence = [
+        Rectangle {
+            width: 1.0,
+            height: 2.0,
+        },
+        Rectangle {
+            width: 3.0,
+            height: 3.0,
+        },
+        Rectangle {
+            width: 0.5,
+            height: 4.0,
+        },
+    ];
+    println!("Total rectangle perimeter: {:.2}", total_perimeter(&fence));
+
+    let mut subject = Subject::new();
+    subject.subscribe(Box::new(PrintObserver {
+        name: String::from("first"),
+    }));
+    subject.subscribe(Box::new(PrintObserver {
+        name: String::from("second"),
+    }));
+    subject.broadcast("shapes computed");
+
+    for shape in [as_shape(&circle), as_shape(&rectangle)] {
+        println!("Shape via &dyn Shape has area {:.2}", shape.area());
+    }
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(Rectangle {
+            width: 2.0,
+            height: 2.0,
+        }),
+        Box::new(Circle { radius: 3.0 }),
+        Box::new(Circle { radius: 1.0 }),
+    ];
+    if let Some(index) = index_of_largest(&shapes) {
+        println!("Largest shape is at index {}", index);
+    }
+    if let Some(shape) = largest_shape(&shapes) {
+        println!("Largest shape area: {:.2}", shape.area());
+    }
+    if let Some(perimeter) = smallest_perimeter(&shapes) {
+        println!("Smallest perimeter: {:.2}", perimeter);
+    }
+    if let Err(e) = report_shapes(&mut std::io::stdout(), &shapes) {
+        return SectionResult::failed("Generics and Traits", e.to_string());
+    }
+
+    let circles = [Circle { radius: 1.0 }, Circle { radius: 2.5 }];
+    println!("{}", write_lines(&circles));
+    println!("{}", point.to_line());
+
+    let path = vec![Point { x: 0.0, y: 1.5 }, Point { x: 2.0, y: -3.0 }];
+    let coords = flatten_points(&path);
+    println!("Flattened points: {:?}", coords);
+    println!("Round-tripped points: {:?}", unflatten_points(&coords));
+
+    let mut canvas = String::new();
+    circle.draw(&mut canvas);
+    rectangle.draw(&mut canvas);
+    print!("{}", canvas);
+
+    let mut stack = Stack::new();
+    stack.push("bottom");
+    stack.push("middle");
+    stack.push("top");
+    let items: Vec<_> = stack.iter_items().collect();
+    println!("Stack items: {:?}", items);
+
+    let a = Complex { re: 1.0, im: 2.0 };
+    let b = Complex { re: 3.0, im: -1.0 };
+    println!("({}) + ({}) = {}", a, b, a + b);
+    println!("({}) * ({}) = {}", a, b, a * b);
+
+    SectionResult::ok(
+        "Generics and Traits",
+        format!("circle area {:.2}", circle.area()),
+    )
 }
 
+#[derive(Debug, PartialEq)]
 struct Point<T> {
     x: T,
     y: T,
 }
 
+fn flatten_points(points: &[Point<f64>]) -> Vec<f64> {
+    points.iter().flat_map(|p| [p.x, p.y]).collect()
+}
+
+fn unflatten_points(coords: &[f64]) -> Result<Vec<Point<f64>>, String> {
+    if !coords.len().is_multiple_of(2) {
+        return Err(format!(
+            "expected an even number of coordinates, got {}",
+            coords.len()
+        ));
+    }
+    Ok(coords
+        .chunks_exact(2)
+        .map(|pair| Point {
+            x: pair[0],
+            y: pair[1],
+        })
+        .collect())
+}
+
 trait Shape {
     fn area(&self) -> f64;
+    fn perimeter(&self) -> f64;
+    fn bounding_box(&self) -> (f64, f64);
 }
 
 struct Circle {
@@ -84,148 +480,3892 @@ impl Shape for Circle {
     fn area(&self) -> f64 {
         PI * self.radius * self.radius
     }
+
+    fn perimeter(&self) -> f64 {
+        self.circumference()
+    }
+
+    fn bounding_box(&self) -> (f64, f64) {
+        let diameter = 2.0 * self.radius;
+        (diameter, diameter)
+    }
 }
 
-// Section 3: Enums and Pattern Matching
-fn enums_and_pattern_matching() {
-    println!("\n--- Enums and Pattern Matching ---");
+impl std::fmt::Debug for Circle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Circle {{ radius: {} units, area: {:.2} }}",
+            self.radius,
+            self.area()
+        )
+    }
+}
 
-    let message = Message::Hello(String::from("Rust"));
-    match message {
-        Message::Hello(msg) => println!("Received message: {}", msg),
-        Message::Quit => println!("Quitting"),
+impl Circle {
+    fn circumference(&self) -> f64 {
+        2.0 * PI * self.radius
     }
 }
 
-enum Message {
-    Hello(String),
-    Quit,
+const fn circle_area_const(radius: f64) -> f64 {
+    PI * radius * radius
 }
 
-// Section 4: Error Handling
-fn error_handling() {
-    println!("\n--- Error Handling ---");
+struct Rectangle {
+    width: f64,
+    height: f64,
+}
 
-    let filepath = "nonexistent_file.txt";
-    match std::fs::read_to_string(filepath) {
-        Ok(content) => println!("File content: {}", content),
-        Err(e) => println!("Error reading file: {}", e),
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.width + self.height)
+    }
+
+    fn bounding_box(&self) -> (f64, f64) {
+        (self.width, self.height)
     }
 }
 
-// Section 5: Iterators and Closures
-fn iterators_and_closures() {
-    println!("\n--- Iterators and Closures ---");
+trait Drawable {
+    fn draw(&self, out: impl std::fmt::Write);
+}
 
-    let numbers = vec![1, 2, 3, 4];
-    let doubled: Vec<_> = numbers.iter().map(|x| x * 2).collect();
-    println!("Doubled numbers: {:?}", doubled);
+impl Drawable for Circle {
+    fn draw(&self, mut out: impl std::fmt::Write) {
+        // Writing is best-effort; the trait has no way to surface errors.
+        let _ = writeln!(out, "( circle r={} )", self.radius);
+    }
+}
 
-    let even_numbers: Vec<_> = numbers.into_iter().filter(|x| x % 2 == 0).collect();
-    println!("Even numbers: {:?}", even_numbers);
+impl Drawable for Rectangle {
+    fn draw(&self, mut out: impl std::fmt::Write) {
+        let _ = writeln!(out, "[ rectangle {}x{} ]", self.width, self.height);
+    }
 }
 
-// Section 6: Async Programming
-fn async_runtime_demo() {
-    println!("\n--- Async Programming ---");
+fn as_shape<S: Shape + 'static>(s: &S) -> &dyn Shape {
+    s
+}
 
-    let runtime = tokio::runtime::Runtime::new().unwrap();
-    runtime.block_on(async {
-        let handle = tokio::spawn(async_task());
-        handle.await.unwrap();
-    });
+fn index_of_largest(shapes: &[Box<dyn Shape>]) -> Option<usize> {
+    shapes
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.area().total_cmp(&b.area()))
+        .map(|(index, _)| index)
 }
 
-async fn async_task() {
-    println!("Async task started...");
-    tokio::time::sleep(Duration::from_secs(2)).await;
-    println!("Async task finished!");
+fn largest_shape(shapes: &[Box<dyn Shape>]) -> Option<&dyn Shape> {
+    // total_cmp orders NaN consistently instead of panicking like partial_cmp().unwrap().
+    shapes
+        .iter()
+        .max_by(|a, b| a.area().total_cmp(&b.area()))
+        .map(|shape| shape.as_ref())
 }
 
-// Section 7: Multithreading with Mutex
-fn multithreading_with_mutex() {
-    println!("\n--- Multithreading with Mutex ---");
+fn smallest_perimeter(shapes: &[Box<dyn Shape>]) -> Option<f64> {
+    shapes
+        .iter()
+        .map(|shape| shape.perimeter())
+        .min_by(|a, b| a.total_cmp(b))
+}
 
-    let counter = Arc::new(Mutex::new(0));
-    let mut handles = vec![];
+fn total_area<S: Shape + ?Sized>(shapes: &[Box<S>]) -> f64 {
+    shapes.iter().map(|shape| shape.area()).sum()
+}
 
-    for _ in 0..5 {
-        let counter = Arc::clone(&counter);
-        let handle = thread::spawn(move || {
-            let mut num = counter.lock().unwrap();
-            *num += 1;
-        });
-        handles.push(handle);
+// For shapes that only need an outline length, without the rest of `Shape`.
+// Types implementing both traits need `Perimeter::perimeter(&x)` to call it.
+trait Perimeter {
+    fn perimeter(&self) -> f64;
+}
+
+impl Perimeter for Circle {
+    fn perimeter(&self) -> f64 {
+        self.circumference()
     }
+}
 
-    for handle in handles {
-        handle.join().unwrap();
+impl Perimeter for Rectangle {
+    fn perimeter(&self) -> f64 {
+        Shape::perimeter(self)
     }
+}
 
-    println!("Counter value: {}", *counter.lock().unwrap());
+fn total_perimeter<T: Perimeter>(items: &[T]) -> f64 {
+    items.iter().map(Perimeter::perimeter).sum()
 }
 
-// Section 8: Smart Pointers
-fn smart_pointers_demo() {
-    println!("\n--- Smart Pointers ---");
+fn report_shapes(out: &mut impl std::io::Write, shapes: &[Box<dyn Shape>]) -> std::io::Result<()> {
+    for (index, shape) in shapes.iter().enumerate() {
+        writeln!(
+            out,
+            "Shape {}: area {:.2}, perimeter {:.2}",
+            index,
+            shape.area(),
+            shape.perimeter()
+        )?;
+    }
+    let (total, min, max) = shape_stats(shapes);
+    writeln!(
+        out,
+        "Shape areas: total {:.2}, min {:.2}, max {:.2}",
+        total, min, max
+    )
+}
 
-    let boxed_value = Box::new(42);
-    println!("Boxed value: {}", boxed_value);
+fn shape_stats(shapes: &[Box<dyn Shape>]) -> (f64, f64, f64) {
+    if shapes.is_empty() {
+        return (0.0, 0.0, 0.0);
+    }
+    shapes.iter().fold(
+        (0.0, f64::INFINITY, f64::NEG_INFINITY),
+        |(total, min, max), shape| {
+            let area = shape.area();
+            (total + area, min.min(area), max.max(area))
+        },
+    )
+}
 
-    let rc_value = std::rc::Rc::new(String::from("Shared"));
-    println!("RC value: {}", rc_value);
+trait Observer {
+    fn notify(&self, event: &str);
 }
 
-// Section 9: Collections
-fn collections_demo() {
-    println!("\n--- Collections ---");
+struct PrintObserver {
+    name: String,
+}
 
-    let mut hashmap = HashMap::new();
-    hashmap.insert("Key1", 100);
-    hashmap.insert("Key2", 200);
+impl Observer for PrintObserver {
+    fn notify(&self, event: &str) {
+        println!("Observer '{}' received: {}", self.name, event);
+    }
+}
+
+struct Subject {
+    observers: Vec<Box<dyn Observer>>,
+}
+
+impl Subject {
+    fn new() -> Self {
+        Subject {
+            observers: Vec::new(),
+        }
+    }
+
+    fn subscribe(&mut self, observer: Box<dyn Observer>) {
+        self.observers.push(observer);
+    }
 
-    for (key, value) in &hashmap {
-        println!("{}: {}", key, value);
+    fn broadcast(&self, event: &str) {
+        for observer in &self.observers {
+            observer.notify(event);
+        }
     }
 }
 
-// Section 10: Macros
-macro_rules! custom_macro {
-    ($msg:expr) => {
-        println!("Custom macro says: {}", $msg);
+trait Container {
+    type Item;
+    fn iter_items(&self) -> Box<dyn Iterator<Item = &Self::Item> + '_>;
+}
+
+struct Stack<T> {
+    items: Vec<T>,
+}
+
+impl<T> Stack<T> {
+    fn new() -> Self {
+        Stack { items: Vec::new() }
+    }
+
+    fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+}
+
+impl<T> Container for Stack<T> {
+    type Item = T;
+
+    fn iter_items(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        Box::new(self.items.iter())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl std::ops::Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex {
+            re: self.re + other.re,
+            im: self.im + other.im,
+        }
+    }
+}
+
+impl std::ops::Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex {
+            re: self.re * other.re - self.im * other.im,
+            im: self.re * other.im + self.im * other.re,
+        }
+    }
+}
+
+impl std::fmt::Display for Complex {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.im < 0.0 {
+            write!(f, "{} - {}i", self.re, -self.im)
+        } else {
+            write!(f, "{} + {}i", self.re, self.im)
+        }
+    }
+}
+
+trait ToLine {
+    fn to_line(&self) -> String;
+}
+
+impl ToLine for Point<i32> {
+    fn to_line(&self) -> String {
+        format!("point {} {}", self.x, self.y)
+    }
+}
+
+impl ToLine for Circle {
+    fn to_line(&self) -> String {
+        format!("circle {}", self.radius)
+    }
+}
+
+fn write_lines<T: ToLine>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(ToLine::to_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Section 3: Enums and Pattern Matching
+fn enums_and_pattern_matching() -> SectionResult {
+    println!("\n--- Enums and Pattern Matching ---");
+
+    let message = Message::Hello(String::from("Rust"));
+    match message {
+        Message::Hello(msg) => println!("Received message: {}", msg),
+        Message::Quit => println!("Quitting"),
+    }
+
+    let router = Router {
+        on_hello: Box::new(|msg| println!("Router says hello to {}", msg)),
+        on_quit: Box::new(|| println!("Router handled quit")),
     };
+    router.route(&Message::Hello(String::from("Router")));
+    router.route(&Message::Quit);
+
+    let bus = EventBus::new()
+        .on(|event| println!("Logger saw: {}", event))
+        .on(|event| println!("Metrics counted: {}", event))
+        .on(|event| println!("Auditor recorded: {}", event));
+    bus.emit("user_signed_in");
+
+    let mut light = TrafficLight::Red;
+    for _ in 0..4 {
+        let action = if matches!(light, TrafficLight::Green) {
+            "go"
+        } else {
+            "wait"
+        };
+        println!("Traffic light {:?}: {}", light, action);
+        light = light.next();
+    }
+
+    for op in [Op::Add, Op::Sub, Op::Mul, Op::Div] {
+        match apply(op, 8.0, 2.0) {
+            Ok(value) => println!("{:?}(8, 2) = {}", op, value),
+            Err(e) => println!("{:?}(8, 2) failed: {}", op, e),
+        }
+    }
+    if let Err(e) = apply(Op::Div, 1.0, 0.0) {
+        println!("Div(1, 0) failed: {}", e);
+    }
+
+    let values: Vec<Either<i32, String>> =
+        vec![Either::Left(21), Either::Right(String::from("rust"))];
+    for value in values {
+        let is_left = value.is_left();
+        let mapped = value.map_left(|n| n * 2).map_right(|s| s.to_uppercase());
+        println!("Either (left: {}) mapped to {:?}", is_left, mapped);
+    }
+
+    SectionResult::ok("Enums and Pattern Matching", "message matched")
 }
 
-fn macros_demo() {
-    println!("\n--- Macros ---");
-    custom_macro!("Hello from a macro!");
+enum Message {
+    Hello(String),
+    Quit,
 }
 
-// Section 11: Command-Line Arguments
-fn command_line_demo() {
-    println!("\n--- Command-Line Arguments ---");
+impl ToLine for Message {
+    fn to_line(&self) -> String {
+        match self {
+            Message::Hello(msg) => format!("hello {}", msg),
+            Message::Quit => String::from("quit"),
+        }
+    }
+}
 
-    let args: Vec<String> = env::args().collect();
-    if args.len() > 1 {
-        println!("Arguments: {:?}", &args[1..]);
-    } else {
-        println!("No arguments provided.");
+struct Router {
+    on_hello: Box<dyn Fn(&str)>,
+    on_quit: Box<dyn Fn()>,
+}
+
+impl Router {
+    fn route(&self, msg: &Message) {
+        match msg {
+            Message::Hello(text) => (self.on_hello)(text),
+            Message::Quit => (self.on_quit)(),
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+type EventHandler = Box<dyn Fn(&str)>;
 
-    #[test]
-    fn test_calculate_length() {
-        let test_string = String::from("Rust");
-        assert_eq!(calculate_length(&test_string), 4);
+struct EventBus {
+    handlers: Vec<EventHandler>,
+}
+
+impl EventBus {
+    fn new() -> Self {
+        EventBus {
+            handlers: Vec::new(),
+        }
     }
 
-    #[test]
-    fn test_circle_area() {
-        let circle = Circle { radius: 2.0 };
-        assert_eq!(circle.area(), 12.56636);
+    fn on(mut self, handler: impl Fn(&str) + 'static) -> Self {
+        self.handlers.push(Box::new(handler));
+        self
+    }
+
+    fn emit(&self, event: &str) {
+        for handler in &self.handlers {
+            handler(event);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TrafficLight {
+    Red,
+    Green,
+    Yellow,
+}
+
+impl TrafficLight {
+    fn next(self) -> TrafficLight {
+        match self {
+            TrafficLight::Red => TrafficLight::Green,
+            TrafficLight::Green => TrafficLight::Yellow,
+            TrafficLight::Yellow => TrafficLight::Red,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+fn apply(op: Op, a: f64, b: f64) -> Result<f64, String> {
+    match op {
+        Op::Add => Ok(a + b),
+        Op::Sub => Ok(a - b),
+        Op::Mul => Ok(a * b),
+        Op::Div if b == 0.0 => Err(String::from("division by zero")),
+        Op::Div => Ok(a / b),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<L, R> Either<L, R> {
+    fn is_left(&self) -> bool {
+        matches!(self, Either::Left(_))
+    }
+
+    fn map_left<T>(self, f: impl FnOnce(L) -> T) -> Either<T, R> {
+        match self {
+            Either::Left(left) => Either::Left(f(left)),
+            Either::Right(right) => Either::Right(right),
+        }
+    }
+
+    fn map_right<T>(self, f: impl FnOnce(R) -> T) -> Either<L, T> {
+        match self {
+            Either::Left(left) => Either::Left(left),
+            Either::Right(right) => Either::Right(f(right)),
+        }
+    }
+}
+
+// Section 4: Error Handling
+fn error_handling() -> SectionResult {
+    println!("\n--- Error Handling ---");
+
+    let filepath = "nonexistent_file.txt";
+    // A missing file is the expected case here, so both arms count as success.
+    let detail = match read_demo_file(filepath) {
+        Ok(content) => {
+            println!("File content: {}", content);
+            format!("read {} bytes", content.len())
+        }
+        Err(e) => {
+            println!("Error reading file: {}", e);
+            format!("handled error: {}", e)
+        }
+    };
+
+    let lossy_path = env::temp_dir().join("rust_demo_invalid_utf8.txt");
+    let lossy_path = lossy_path.to_string_lossy();
+    if std::fs::write(lossy_path.as_ref(), b"valid \xFF\xFE bytes").is_ok() {
+        match read_bytes_lossy(&lossy_path) {
+            Ok(content) => println!("Lossy file content: {}", content),
+            Err(e) => println!("Error reading file: {}", e),
+        }
+        let _ = std::fs::remove_file(lossy_path.as_ref());
+    }
+
+    let tree_root = env::temp_dir().join("rust_demo_walk");
+    let nested = tree_root.join("nested");
+    let created = std::fs::create_dir_all(&nested)
+        .and_then(|_| std::fs::write(tree_root.join("top.txt"), "top"))
+        .and_then(|_| std::fs::write(nested.join("inner.txt"), "inner"));
+    if created.is_ok() {
+        match walk_dir(&tree_root.to_string_lossy()) {
+            Ok(files) => println!("Walked files: {:?}", files),
+            Err(e) => println!("Error walking directory: {}", e),
+        }
+    }
+    let _ = std::fs::remove_dir_all(&tree_root);
+
+    let config_path = env::temp_dir().join("rust_demo_config.txt");
+    let config_path = config_path.to_string_lossy();
+    if std::fs::write(config_path.as_ref(), "mode=demo").is_ok() {
+        let mode = if global_config().async_io {
+            "async"
+        } else {
+            "sync"
+        };
+        match load_config_file(&config_path) {
+            Ok(content) => println!("Config file ({} read): {}", mode, content),
+            Err(e) => println!("Error reading config file: {}", e),
+        }
+        let _ = std::fs::remove_file(config_path.as_ref());
+    }
+
+    for input in ["42", "forty-two"] {
+        match parse_number(input) {
+            Ok(value) => println!("Parsed '{}' as {}", input, value),
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+    for radius in [2.0, -1.0] {
+        match validate_circle(radius) {
+            Ok(circle) => println!("Valid circle: {:?}", circle),
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+
+    let mut calls = 0;
+    let flaky = retry_sync(3, || {
+        calls += 1;
+        if calls < 3 {
+            Err(format!("attempt {} failed", calls))
+        } else {
+            Ok("connected")
+        }
+    });
+    println!("Flaky operation after {} calls: {:?}", calls, flaky);
+
+    let parsed: Vec<_> = ["1", "two", "3", "4x"]
+        .iter()
+        .map(|s| parse_number(s))
+        .collect();
+    let (numbers, errors) = flatten_results(parsed);
+    println!("Parsed {:?} with {} errors", numbers, errors.len());
+
+    for n in [5, 25] {
+        match factorial(n) {
+            Some(value) => println!("{}! = {}", n, value),
+            None => println!("{}! overflows u64", n),
+        }
+    }
+
+    SectionResult::ok("Error Handling", detail)
+}
+
+#[derive(Debug, thiserror::Error)]
+enum DemoError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("parse error: {0}")]
+    Parse(#[from] std::num::ParseIntError),
+    #[error("invalid shape: {0}")]
+    InvalidShape(String),
+}
+
+fn read_demo_file(path: &str) -> Result<String, DemoError> {
+    Ok(std::fs::read_to_string(path)?)
+}
+
+fn parse_number(input: &str) -> Result<i64, DemoError> {
+    Ok(input.trim().parse()?)
+}
+
+fn validate_circle(radius: f64) -> Result<Circle, DemoError> {
+    if radius.is_finite() && radius > 0.0 {
+        Ok(Circle { radius })
+    } else {
+        Err(DemoError::InvalidShape(format!(
+            "circle radius must be positive, got {}",
+            radius
+        )))
+    }
+}
+
+fn read_bytes_lossy(path: &str) -> Result<String, std::io::Error> {
+    let bytes = std::fs::read(path)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+// Symlinks are listed but never followed, so link cycles can't recurse forever.
+fn walk_dir(path: &str) -> Result<Vec<String>, std::io::Error> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let entry_path = entry.path();
+        if entry.file_type()?.is_dir() {
+            files.extend(walk_dir(&entry_path.to_string_lossy())?);
+        } else {
+            files.push(entry_path.to_string_lossy().into_owned());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn read_config_file(path: &str) -> Result<String, std::io::Error> {
+    std::fs::read_to_string(path)
+}
+
+async fn read_config_file_async(path: &str) -> Result<String, std::io::Error> {
+    tokio::fs::read_to_string(path).await
+}
+
+// Reads the file synchronously, or on a throwaway runtime when DEMO_ASYNC_IO is set.
+fn load_config_file(path: &str) -> Result<String, std::io::Error> {
+    if global_config().async_io {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        runtime.block_on(read_config_file_async(path))
+    } else {
+        read_config_file(path)
+    }
+}
+
+fn flatten_results<T, E>(results: Vec<Result<T, E>>) -> (Vec<T>, Vec<E>) {
+    let mut oks = Vec::new();
+    let mut errs = Vec::new();
+    for result in results {
+        match result {
+            Ok(value) => oks.push(value),
+            Err(e) => errs.push(e),
+        }
+    }
+    (oks, errs)
+}
+
+// Always makes at least one attempt, even when `attempts` is 0.
+fn retry_sync<T, E>(attempts: usize, mut f: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+    let mut result = f();
+    for _ in 1..attempts {
+        if result.is_ok() {
+            break;
+        }
+        result = f();
+    }
+    result
+}
+
+fn factorial(n: u64) -> Option<u64> {
+    let mut result: u64 = 1;
+    for i in 2..=n {
+        result = result.checked_mul(i)?;
+    }
+    Some(result)
+}
+
+// Section 5: Iterators and Closures
+fn iterators_and_closures() -> SectionResult {
+    println!("\n--- Iterators and Closures ---");
+
+    let numbers = vec![1, 2, 3, 4];
+    let doubled: Vec<_> = numbers.iter().map(|x| x * 2).collect();
+    println!("Doubled numbers: {}", join_display(&doubled, ", "));
+
+    let even_numbers: Vec<_> = numbers.into_iter().filter(|x| x % 2 == 0).collect();
+    println!("Even numbers: {}", join_display(&even_numbers, ", "));
+
+    let sums = zip_with(&[1, 2, 3], &[10, 20, 30], |a, b| a + b);
+    println!("Elementwise sums: {}", join_display(&sums, ", "));
+    println!(
+        "Zipped triples: {:?}",
+        zip3(&[1, 2, 3], &[1.5, 2.5, 3.5], &[10u8, 20])
+    );
+
+    let fahrenheit = convert_all(&[-40.0, 0.0, 37.0, 100.0]);
+    println!("Temperatures in Fahrenheit: {:?}", fahrenheit);
+    println!(
+        "Body temperature back in Celsius: {:.1}",
+        fahrenheit_to_celsius(fahrenheit[2])
+    );
+
+    let flattened = flatten(&[vec![1, 2], vec![3], vec![4, 5]]);
+    println!("Flattened: {}", join_display(&flattened, ", "));
+
+    let (evens, odds) = partition(&[1, 2, 3, 4, 5, 6], |x| x % 2 == 0);
+    println!("Partitioned evens: {:?}, odds: {:?}", evens, odds);
+
+    let rotated = rotate_left(&[1, 2, 3, 4, 5], 2);
+    println!("Rotated left by 2: {}", join_display(&rotated, ", "));
+
+    let window_totals = window_sums(&[1, 2, 3, 4], 2);
+    println!("Window sums: {}", join_display(&window_totals, ", "));
+
+    let add_five = make_adder(5);
+    let add_ten = make_adder(10);
+    println!("add_five(1) = {}, add_ten(1) = {}", add_five(1), add_ten(1));
+
+    let stages: Vec<Stage> = vec![Box::new(|x| x + 1), Box::new(|x| x * 2)];
+    println!("Pipeline(+1, *2) on 3 = {}", pipeline(stages, 3));
+
+    match find_first(&[1, 2, 3, 4], |&x| x > 2) {
+        Some(x) => println!("First number greater than 2: {}", x),
+        None => println!("No number greater than 2"),
+    }
+
+    println!("Interleaved: {:?}", interleave(&[1, 3, 5], &[2, 4]));
+
+    for (position, name) in enumerate_from(&["first", "second", "third"], 1) {
+        println!("{}. {}", position, name);
+    }
+
+    println!("Clamped: {:?}", clamp_all(&[-1.0, 0.5, 2.0], 0.0, 1.0));
+
+    println!(
+        "Adjacent duplicates removed: {:?}",
+        dedup_adjacent(&[1, 1, 2, 3, 3, 3, 1])
+    );
+
+    let records = [(1, "alice"), (2, "bob"), (1, "alice (dup)"), (3, "carol")];
+    println!(
+        "Records deduplicated by id: {:?}",
+        dedup_by_key(&records, |&(id, _)| id)
+    );
+
+    for divisor in [2.0, 0.0] {
+        match safe_divide(&[1.0, 4.0, 9.0], divisor) {
+            Ok(values) => println!("Divided by {}: {:?}", divisor, values),
+            Err(e) => println!("Divide by {} failed: {}", divisor, e),
+        }
+    }
+
+    println!("argmax of [3, 9, 2, 9]: {:?}", argmax(&[3, 9, 2, 9]));
+
+    println!(
+        "Sum until negative of [1, 2, -1, 3]: {}",
+        sum_until_negative(&[1, 2, -1, 3])
+    );
+
+    let samples = [0.5, 1.2, 2.8, 3.3, 3.9, 9.5, -2.0];
+    println!(
+        "Histogram over [0, 4) in 4 bins: {:?}",
+        histogram(&samples, 4, 0.0, 4.0)
+    );
+
+    let sorted = [1, 3, 3, 5, 8];
+    println!(
+        "lower_bound of 3 in {:?}: {}, of 4: {}",
+        sorted,
+        lower_bound(&sorted, &3),
+        lower_bound(&sorted, &4)
+    );
+
+    println!(
+        "Taken while sum <= 5: {:?}",
+        take_while_sum(&[1, 2, 3, 4], 5)
+    );
+
+    println!(
+        "Rolling median (window 3): {:?}",
+        rolling_median(&[5.0, 1.0, 4.0, 2.0, 8.0], 3)
+    );
+
+    SectionResult::ok("Iterators and Closures", format!("doubled {:?}", doubled))
+}
+
+fn zip_with<A, B, C>(a: &[A], b: &[B], f: impl Fn(&A, &B) -> C) -> Vec<C> {
+    a.iter().zip(b.iter()).map(|(x, y)| f(x, y)).collect()
+}
+
+// Stops at the shortest of the three slices.
+fn zip3<A: Clone, B: Clone, C: Clone>(a: &[A], b: &[B], c: &[C]) -> Vec<(A, B, C)> {
+    a.iter()
+        .zip(b)
+        .zip(c)
+        .map(|((x, y), z)| (x.clone(), y.clone(), z.clone()))
+        .collect()
+}
+
+fn celsius_to_fahrenheit(c: f64) -> f64 {
+    c * 9.0 / 5.0 + 32.0
+}
+
+fn fahrenheit_to_celsius(f: f64) -> f64 {
+    (f - 32.0) * 5.0 / 9.0
+}
+
+fn convert_all(temps: &[f64]) -> Vec<f64> {
+    temps.iter().map(|&c| celsius_to_fahrenheit(c)).collect()
+}
+
+fn flatten(nested: &[Vec<i32>]) -> Vec<i32> {
+    nested
+        .iter()
+        .flat_map(|inner| inner.iter().copied())
+        .collect()
+}
+
+fn partition<T: Clone>(items: &[T], pred: impl Fn(&T) -> bool) -> (Vec<T>, Vec<T>) {
+    items.iter().cloned().partition(|item| pred(item))
+}
+
+fn rotate_left<T: Clone>(items: &[T], n: usize) -> Vec<T> {
+    if items.is_empty() {
+        return Vec::new();
+    }
+    let shift = n % items.len();
+    items[shift..]
+        .iter()
+        .chain(&items[..shift])
+        .cloned()
+        .collect()
+}
+
+fn window_sums(nums: &[i64], window: usize) -> Vec<i64> {
+    if window == 0 || window > nums.len() {
+        return Vec::new();
+    }
+    nums.windows(window).map(|w| w.iter().sum()).collect()
+}
+
+fn make_adder(n: i32) -> Box<dyn Fn(i32) -> i32> {
+    Box::new(move |x| x + n)
+}
+
+fn find_first<T>(items: &[T], pred: impl Fn(&T) -> bool) -> Option<&T> {
+    items.iter().find(|item| pred(item))
+}
+
+fn interleave<T: Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut result = Vec::with_capacity(a.len() + b.len());
+    for (x, y) in a.iter().zip(b) {
+        result.push(x.clone());
+        result.push(y.clone());
+    }
+    let common = a.len().min(b.len());
+    result.extend_from_slice(&a[common..]);
+    result.extend_from_slice(&b[common..]);
+    result
+}
+
+fn enumerate_from<T>(items: &[T], start: usize) -> Vec<(usize, &T)> {
+    (start..).zip(items).collect()
+}
+
+fn clamp_all(nums: &[f64], lo: f64, hi: f64) -> Vec<f64> {
+    assert!(lo <= hi, "clamp_all requires lo <= hi, got {} > {}", lo, hi);
+    nums.iter().map(|x| x.clamp(lo, hi)).collect()
+}
+
+fn dedup_adjacent<T: PartialEq + Clone>(items: &[T]) -> Vec<T> {
+    let mut result: Vec<T> = Vec::with_capacity(items.len());
+    for item in items {
+        if result.last() != Some(item) {
+            result.push(item.clone());
+        }
+    }
+    result
+}
+
+// Unlike `dedup_adjacent`, duplicates need not be next to each other.
+fn dedup_by_key<T: Clone, K: Eq + std::hash::Hash>(
+    items: &[T],
+    key_fn: impl Fn(&T) -> K,
+) -> Vec<T> {
+    let mut seen = HashSet::new();
+    items
+        .iter()
+        .filter(|item| seen.insert(key_fn(item)))
+        .cloned()
+        .collect()
+}
+
+type Stage = Box<dyn Fn(i32) -> i32>;
+
+fn pipeline(stages: Vec<Stage>, input: i32) -> i32 {
+    stages.iter().fold(input, |value, stage| stage(value))
+}
+
+fn safe_divide(nums: &[f64], divisor: f64) -> Result<Vec<f64>, String> {
+    if divisor == 0.0 {
+        return Err(String::from("division by zero"));
+    }
+    Ok(nums.iter().map(|x| x / divisor).collect())
+}
+
+// Only a strictly greater element replaces the current max, so ties keep the first index.
+fn argmax<T: PartialOrd>(items: &[T]) -> Option<usize> {
+    let mut best: Option<usize> = None;
+    for (index, item) in items.iter().enumerate() {
+        if best.is_none_or(|current| *item > items[current]) {
+            best = Some(index);
+        }
+    }
+    best
+}
+
+fn sum_until_negative(nums: &[i64]) -> i64 {
+    use std::ops::ControlFlow;
+
+    let outcome = nums.iter().try_fold(0, |sum, &x| {
+        if x < 0 {
+            ControlFlow::Break(sum)
+        } else {
+            ControlFlow::Continue(sum + x)
+        }
+    });
+    match outcome {
+        ControlFlow::Break(sum) | ControlFlow::Continue(sum) => sum,
+    }
+}
+
+// Bins are half-open `[lo, hi)`; values outside `[min, max)` land in the first or last bin.
+// NaN values belong to no bin and are skipped.
+fn histogram(values: &[f64], bins: usize, min: f64, max: f64) -> Vec<usize> {
+    assert!(
+        max > min,
+        "histogram requires max > min, got {} <= {}",
+        max,
+        min
+    );
+    let mut counts = vec![0; bins];
+    if bins == 0 {
+        return counts;
+    }
+    let width = (max - min) / bins as f64;
+    for value in values.iter().filter(|value| !value.is_nan()) {
+        let bin = ((value - min) / width).floor().max(0.0) as usize;
+        counts[bin.min(bins - 1)] += 1;
+    }
+    counts
+}
+
+fn lower_bound<T: Ord>(sorted: &[T], target: &T) -> usize {
+    sorted.partition_point(|item| item < target)
+}
+
+fn take_while_sum(nums: &[i64], limit: i64) -> Vec<i64> {
+    let mut sum = 0;
+    nums.iter()
+        .take_while(|&&x| {
+            sum += x;
+            sum <= limit
+        })
+        .copied()
+        .collect()
+}
+
+fn rolling_median(nums: &[f64], window: usize) -> Vec<f64> {
+    if window == 0 {
+        return Vec::new();
+    }
+    nums.windows(window)
+        .map(|w| {
+            let mut sorted = w.to_vec();
+            sorted.sort_by(f64::total_cmp);
+            let mid = window / 2;
+            if window % 2 == 1 {
+                sorted[mid]
+            } else {
+                (sorted[mid - 1] + sorted[mid]) / 2.0
+            }
+        })
+        .collect()
+}
+
+// Joins the `Display` form of each item, e.g. "2, 4, 6" rather than "[2, 4, 6]".
+fn join_display<T: std::fmt::Display>(items: &[T], sep: &str) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+// Section 6: Async Programming
+fn async_runtime_demo() -> SectionResult {
+    println!("\n--- Async Programming ---");
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => return SectionResult::failed("Async Programming", e.to_string()),
+    };
+    let shutdown_log = Arc::new(Mutex::new(Vec::new()));
+    // Listen for Ctrl-C across every step below, not just the final join.
+    let outcome = runtime.block_on(run_with_signal(async {
+        let _guard = ShutdownGuard {
+            name: String::from("async demo"),
+            log: Arc::clone(&shutdown_log),
+        };
+        let verbose = global_config().verbose;
+        let handle = tokio::spawn(async_task(verbose, Duration::from_secs(2)));
+        let streamed = collect_stream(5).await;
+        println!("Streamed values: {:?}", streamed);
+
+        let debouncer = Debouncer {
+            delay: Duration::from_millis(200),
+        };
+        let mut last_event = Instant::now();
+        for event in 1..=3 {
+            // Rapid events keep pushing the quiet period forward.
+            println!("Event {} received", event);
+            last_event = Instant::now();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        debouncer
+            .call(last_event, || println!("Debounced action fired"))
+            .await;
+
+        let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel();
+        let _ = cancel_tx.send(());
+        println!("Work with cancel: {}", cancellable_work(cancel_rx).await);
+        let (_keep_alive, cancel_rx) = tokio::sync::oneshot::channel();
+        println!("Work without cancel: {}", cancellable_work(cancel_rx).await);
+
+        let ticks = ticker(Duration::from_millis(100), 3).await;
+        println!("Ticker observed {} ticks", ticks);
+
+        let delays = vec![
+            Duration::from_millis(150),
+            Duration::from_millis(50),
+            Duration::from_millis(100),
+        ];
+        println!("Joined futures: {:?}", run_all(delays).await);
+
+        let (tx_a, rx_a) = tokio::sync::mpsc::channel(4);
+        let (tx_b, rx_b) = tokio::sync::mpsc::channel(4);
+        tokio::spawn(async move {
+            for value in [1, 3, 5] {
+                let _ = tx_a.send(value).await;
+            }
+        });
+        tokio::spawn(async move {
+            for value in [2, 4] {
+                let _ = tx_b.send(value).await;
+            }
+        });
+        println!("Multiplexed values: {:?}", multiplex(rx_a, rx_b).await);
+
+        let cache = AsyncCache::new();
+        for _ in 0..2 {
+            let value = cache
+                .get_or_compute("greeting", async {
+                    println!("Computing greeting...");
+                    String::from("hello")
+                })
+                .await;
+            println!("Cached greeting: {}", value);
+        }
+
+        let mut rng = XorShift::new(42);
+        let mut calls = 0;
+        let (result, waits) = retry_jitter(
+            4,
+            Duration::from_millis(10),
+            Duration::from_millis(20),
+            &mut rng,
+            || {
+                calls += 1;
+                let call = calls;
+                async move {
+                    if call < 3 {
+                        Err(format!("attempt {} failed", call))
+                    } else {
+                        Ok(call)
+                    }
+                }
+            },
+        )
+        .await;
+        println!("Retry with jitter: {:?} after waiting {:?}", result, waits);
+        handle.await
+    }));
+
+    println!("Shutdown log: {:?}", shutdown_log.lock().unwrap());
+
+    match outcome {
+        Some(Ok(log)) => SectionResult::ok(
+            "Async Programming",
+            format!("task completed, {} log lines", log.len()),
+        ),
+        Some(Err(e)) => SectionResult::failed("Async Programming", e.to_string()),
+        None => SectionResult::failed("Async Programming", "interrupted by Ctrl-C"),
+    }
+}
+
+// Wall-clock milliseconds since the Unix epoch.
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+// Printed lines carry the elapsed time so concurrent tasks can be told
+// apart; the returned log keeps the bare messages.
+async fn async_task(verbose: bool, duration: Duration) -> Vec<String> {
+    let start = now_millis();
+    let mut log = Vec::new();
+    let mut emit = |line: &str| {
+        if verbose {
+            println!("[+{}ms] {}", now_millis().saturating_sub(start), line);
+            log.push(line.to_string());
+        }
+    };
+
+    emit("Async task started...");
+    tokio::time::sleep(duration).await;
+    emit("Async task finished!");
+    log
+}
+
+// Returns `None` if Ctrl-C arrives before `task` finishes.
+async fn run_with_signal<T>(task: impl std::future::Future<Output = T>) -> Option<T> {
+    run_until_shutdown(task, wait_for_shutdown(shutdown_signal().subscribe())).await
+}
+
+async fn wait_for_shutdown(mut shutdown: tokio::sync::watch::Receiver<bool>) {
+    // With the sender gone, shutdown can never be requested.
+    if shutdown.wait_for(|requested| *requested).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+async fn run_until_shutdown<T>(
+    task: impl std::future::Future<Output = T>,
+    shutdown: impl std::future::Future<Output = ()>,
+) -> Option<T> {
+    tokio::select! {
+        value = task => Some(value),
+        () = shutdown => {
+            println!("Shutdown requested, stopping the running task...");
+            None
+        }
+    }
+}
+
+struct ShutdownGuard {
+    name: String,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Drop for ShutdownGuard {
+    fn drop(&mut self) {
+        let message = format!("{} shut down", self.name);
+        println!("{}", message);
+        lock_recover(&self.log).push(message);
+    }
+}
+
+struct Debouncer {
+    delay: Duration,
+}
+
+impl Debouncer {
+    async fn call<F: FnOnce()>(&self, last_event: Instant, f: F) {
+        let elapsed = last_event.elapsed();
+        if elapsed < self.delay {
+            tokio::time::sleep(self.delay - elapsed).await;
+        }
+        f();
+    }
+}
+
+const LONG_WORK_DURATION: Duration = Duration::from_millis(500);
+
+async fn cancellable_work(cancel: tokio::sync::oneshot::Receiver<()>) -> &'static str {
+    // A dropped sender disables the cancel branch instead of cancelling.
+    tokio::select! {
+        _ = tokio::time::sleep(LONG_WORK_DURATION) => "completed",
+        Ok(()) = cancel => "cancelled",
+    }
+}
+
+async fn ticker(interval: Duration, ticks: usize) -> usize {
+    let mut timer = tokio::time::interval(interval);
+    let mut observed = 0;
+    while observed < ticks {
+        // The first tick completes immediately; later ones wait a full interval.
+        timer.tick().await;
+        observed += 1;
+    }
+    observed
+}
+
+async fn run_all(delays: Vec<Duration>) -> Vec<u64> {
+    let futures = delays
+        .into_iter()
+        .enumerate()
+        .map(|(index, delay)| async move {
+            tokio::time::sleep(delay).await;
+            index as u64
+        });
+    // join_all yields results in input order, whatever order the sleeps finish in.
+    futures::future::join_all(futures).await
+}
+
+struct AsyncCache {
+    inner: tokio::sync::Mutex<HashMap<String, String>>,
+}
+
+impl AsyncCache {
+    fn new() -> Self {
+        AsyncCache {
+            inner: tokio::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn get_or_compute(
+        &self,
+        key: &str,
+        compute: impl std::future::Future<Output = String>,
+    ) -> String {
+        // Holding the lock across `compute` means concurrent misses compute only once.
+        let mut map = self.inner.lock().await;
+        if let Some(value) = map.get(key) {
+            return value.clone();
+        }
+        let value = compute.await;
+        map.insert(key.to_string(), value.clone());
+        value
+    }
+}
+
+async fn multiplex(
+    mut a: tokio::sync::mpsc::Receiver<i32>,
+    mut b: tokio::sync::mpsc::Receiver<i32>,
+) -> Vec<i32> {
+    let mut received = Vec::new();
+    loop {
+        // A closed channel yields None, which disables its branch; `else` fires once both are.
+        tokio::select! {
+            Some(value) = a.recv() => received.push(value),
+            Some(value) = b.recv() => received.push(value),
+            else => break,
+        }
+    }
+    received
+}
+
+// Source of pseudo-random numbers, so tests can pass in a seeded generator.
+trait RngLike {
+    fn next_u64(&mut self) -> u64;
+}
+
+// Small deterministic xorshift64 generator; not suitable for cryptography.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        // An all-zero state would only ever produce zeros.
+        XorShift(seed.max(1))
+    }
+}
+
+impl RngLike for XorShift {
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+}
+
+// Exponential backoff from `base_delay` plus up to `max_jitter` of random
+// delay, so many clients retrying at once don't all wake together. Returns
+// the final result along with the delays that were slept.
+async fn retry_jitter<T, E, Fut>(
+    attempts: usize,
+    base_delay: Duration,
+    max_jitter: Duration,
+    rng: &mut impl RngLike,
+    mut f: impl FnMut() -> Fut,
+) -> (Result<T, E>, Vec<Duration>)
+where
+    Fut: std::future::Future<Output = Result<T, E>>,
+{
+    let mut waits = Vec::new();
+    let mut result = f().await;
+    for retry in 1..attempts {
+        if result.is_ok() {
+            break;
+        }
+        let backoff = base_delay.saturating_mul(1 << (retry - 1).min(16));
+        let jitter_ms = rng
+            .next_u64()
+            .checked_rem(max_jitter.as_millis() as u64)
+            .unwrap_or(0);
+        let delay = backoff + Duration::from_millis(jitter_ms);
+        waits.push(delay);
+        tokio::time::sleep(delay).await;
+        result = f().await;
+    }
+    (result, waits)
+}
+
+async fn collect_stream(n: usize) -> Vec<usize> {
+    use tokio_stream::StreamExt;
+
+    tokio_stream::iter(0..n).collect().await
+}
+
+// Section 7: Multithreading with Mutex
+fn multithreading_with_mutex() -> SectionResult {
+    println!("\n--- Multithreading with Mutex ---");
+
+    let value = run_counter(5);
+    println!("Counter value: {}", value);
+
+    let deferred = run_after(Duration::from_millis(100), || {
+        println!("Deferred task ran after 100ms");
+    });
+    deferred.join().unwrap();
+
+    let registry = Arc::new(Registry::new());
+    registry.insert("requests", 100);
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let registry = Arc::clone(&registry);
+            thread::spawn(move || {
+                for _ in 0..10 {
+                    registry.increment("requests");
+                    registry.increment("errors");
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    println!(
+        "Registry: requests = {:?}, errors = {:?}",
+        registry.get("requests"),
+        registry.get("errors")
+    );
+
+    let queue = Arc::new(BlockingQueue::new(2));
+    let producer_queue = Arc::clone(&queue);
+    let producer = thread::spawn(move || {
+        for value in 1..=5 {
+            producer_queue.push(value);
+        }
+    });
+    let consumed: Vec<i32> = (0..5).map(|_| queue.pop()).collect();
+    producer.join().unwrap();
+    println!("Consumed from queue: {:?} ({} left)", consumed, queue.len());
+
+    println!(
+        "Squares from worker: {:?}",
+        compute_with_channel(vec![1, 2, 3, 4])
+    );
+
+    let spin_lock = Arc::new(SpinLock::new());
+    let spin_counter = Arc::new(AtomicUsize::new(0));
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let spin_lock = Arc::clone(&spin_lock);
+            let spin_counter = Arc::clone(&spin_counter);
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    spin_lock.with_lock(|| {
+                        // A separate load and store would lose updates without the lock.
+                        let current = spin_counter.load(Ordering::Relaxed);
+                        spin_counter.store(current + 1, Ordering::Relaxed);
+                    });
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    println!(
+        "Spinlock-protected counter: {}",
+        spin_counter.load(Ordering::Relaxed)
+    );
+
+    let mut meter = ThroughputMeter::new();
+    for _ in 0..50 {
+        thread::sleep(Duration::from_millis(1));
+        meter.record();
+    }
+    println!("Throughput: {:.0} events/s", meter.per_second());
+
+    let shapes: Vec<Box<dyn Shape + Send>> = (1..=6)
+        .map(|i| Box::new(Circle { radius: i as f64 }) as Box<dyn Shape + Send>)
+        .collect();
+    println!(
+        "Total area across 3 threads: {:.2}",
+        parallel_total_area(shapes, 3)
+    );
+
+    SectionResult::ok("Multithreading with Mutex", format!("counter {}", value))
+}
+
+fn run_counter(threads: usize) -> i32 {
+    let counter = Shared::new(0);
+    let mut handles = vec![];
+
+    for _ in 0..threads {
+        let counter = counter.clone_handle();
+        let handle = thread::spawn(move || {
+            counter.with(|num| *num += 1);
+        });
+        handles.push(handle);
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    counter.with(|num| *num)
+}
+
+// Bundles the Arc<Mutex<T>> pattern so callers don't repeat Arc::clone and lock().unwrap().
+struct Shared<T>(Arc<Mutex<T>>);
+
+impl<T> Shared<T> {
+    fn new(value: T) -> Self {
+        Shared(Arc::new(Mutex::new(value)))
+    }
+
+    fn clone_handle(&self) -> Self {
+        Shared(Arc::clone(&self.0))
+    }
+
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut lock_recover(&self.0))
+    }
+}
+
+// A panic while holding the lock poisons it; the data is still usable here.
+fn lock_recover<T>(m: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn compute_with_channel(inputs: Vec<i64>) -> Vec<i64> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let worker = thread::spawn(move || {
+        for value in inputs {
+            if tx.send(value * value).is_err() {
+                break;
+            }
+        }
+    });
+    // The loop ends once the worker drops its sender.
+    let results = rx.iter().collect();
+    worker.join().unwrap();
+    results
+}
+
+struct SpinLock {
+    locked: AtomicBool,
+}
+
+impl SpinLock {
+    fn new() -> Self {
+        SpinLock {
+            locked: AtomicBool::new(false),
+        }
+    }
+
+    // The lock is released when the returned guard is dropped, including
+    // during unwinding, so a panicking holder can't leave it locked.
+    fn lock(&self) -> SpinLockGuard<'_> {
+        while self
+            .locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            std::hint::spin_loop();
+        }
+        SpinLockGuard { lock: self }
+    }
+
+    fn with_lock<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = self.lock();
+        f()
+    }
+}
+
+struct SpinLockGuard<'a> {
+    lock: &'a SpinLock,
+}
+
+impl Drop for SpinLockGuard<'_> {
+    fn drop(&mut self) {
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
+
+struct ThroughputMeter {
+    start: Instant,
+    count: usize,
+}
+
+impl ThroughputMeter {
+    fn new() -> Self {
+        ThroughputMeter {
+            start: Instant::now(),
+            count: 0,
+        }
+    }
+
+    fn record(&mut self) {
+        self.count += 1;
+    }
+
+    fn per_second(&self) -> f64 {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        if elapsed == 0.0 {
+            0.0
+        } else {
+            self.count as f64 / elapsed
+        }
+    }
+}
+
+// Shapes are moved into worker threads, so the trait objects must also be `Send`.
+fn parallel_total_area(shapes: Vec<Box<dyn Shape + Send>>, num_threads: usize) -> f64 {
+    let chunk_size = shapes.len().div_ceil(num_threads.max(1)).max(1);
+    let mut remaining = shapes.into_iter();
+    let mut handles = Vec::new();
+    loop {
+        let chunk: Vec<_> = remaining.by_ref().take(chunk_size).collect();
+        if chunk.is_empty() {
+            break;
+        }
+        handles.push(thread::spawn(move || total_area(&chunk)));
+    }
+    handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .sum()
+}
+
+fn run_after(delay: Duration, f: impl FnOnce() + Send + 'static) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        thread::sleep(delay);
+        f();
+    })
+}
+
+struct Registry {
+    inner: Mutex<HashMap<String, i32>>,
+}
+
+impl Registry {
+    fn new() -> Self {
+        Registry {
+            inner: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn insert(&self, key: &str, value: i32) {
+        self.inner.lock().unwrap().insert(key.to_string(), value);
+    }
+
+    fn get(&self, key: &str) -> Option<i32> {
+        self.inner.lock().unwrap().get(key).copied()
+    }
+
+    fn increment(&self, key: &str) {
+        *self
+            .inner
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_insert(0) += 1;
+    }
+}
+
+struct BlockingQueue<T> {
+    inner: Mutex<VecDeque<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    cap: usize,
+}
+
+impl<T> BlockingQueue<T> {
+    // Panics if `cap` is 0, since `push` could then never make progress.
+    fn new(cap: usize) -> Self {
+        assert!(cap > 0, "BlockingQueue requires a capacity of at least 1");
+        BlockingQueue {
+            inner: Mutex::new(VecDeque::with_capacity(cap)),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            cap,
+        }
+    }
+
+    fn push(&self, item: T) {
+        let mut queue = self.inner.lock().unwrap();
+        while queue.len() >= self.cap {
+            queue = self.not_full.wait(queue).unwrap();
+        }
+        queue.push_back(item);
+        self.not_empty.notify_one();
+    }
+
+    fn pop(&self) -> T {
+        let mut queue = self.inner.lock().unwrap();
+        loop {
+            if let Some(item) = queue.pop_front() {
+                self.not_full.notify_one();
+                return item;
+            }
+            queue = self.not_empty.wait(queue).unwrap();
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.inner.lock().unwrap().len()
+    }
+}
+
+// Section 8: Smart Pointers
+fn smart_pointers_demo() -> SectionResult {
+    println!("\n--- Smart Pointers ---");
+
+    let boxed_value = Box::new(42);
+    println!("Boxed value: {}", boxed_value);
+
+    let rc_value = std::rc::Rc::new(String::from("Shared"));
+    println!("RC value: {}", rc_value);
+
+    SectionResult::ok(
+        "Smart Pointers",
+        format!("boxed {}, rc {}", boxed_value, rc_value),
+    )
+}
+
+// Section 9: Collections
+fn collections_demo() -> SectionResult {
+    println!("\n--- Collections ---");
+
+    let mut hashmap = HashMap::new();
+    hashmap.insert("Key1", 100);
+    hashmap.insert("Key2", 200);
+    hashmap.insert("LongerKey3", 300);
+
+    let mut rows: Vec<(String, i32)> = hashmap
+        .iter()
+        .map(|(key, value)| (key.to_string(), *value))
+        .collect();
+    rows.sort();
+    if let Err(e) = print_table(&rows) {
+        return SectionResult::failed("Collections", e.to_string());
+    }
+
+    let words = vec!["apple", "pear", "apple", "plum", "pear", "apple"];
+    let mut counts: Vec<_> = value_counts(&words).into_iter().collect();
+    counts.sort();
+    println!("Word counts: {:?}", counts);
+
+    println!("fib_memo(50) = {}", fib_memo(50));
+
+    let mut tasks = TaskQueue::new();
+    tasks.push(1, "write docs");
+    tasks.push(5, "fix outage");
+    tasks.push(3, "review PR");
+    while let Some(task) = tasks.pop() {
+        println!("Next task: {}", task);
+    }
+
+    let mut letters = vec!['a', 'b', 'c', 'd', 'e'];
+    let removed = remove_unordered(&mut letters, 1);
+    println!("Removed {:?}, remaining {:?}", removed, letters);
+
+    println!("Five zeros: {:?}", repeat_vec(0, 5));
+
+    let mut ids = BiMap::new();
+    ids.insert(String::from("alice"), 1);
+    ids.insert(String::from("bob"), 2);
+    println!(
+        "alice -> {:?}, 2 -> {:?}",
+        ids.get_by_key(&String::from("alice")),
+        ids.get_by_value(&2)
+    );
+
+    let mut pairs: Vec<_> = parse_kv("a=1 b=2 bad").into_iter().collect();
+    pairs.sort();
+    println!("Parsed key/value pairs: {:?}", pairs);
+
+    let mut lru = LruCache::new(2);
+    lru.put("config", 1);
+    lru.put("users", 2);
+    lru.get(&"config");
+    lru.put("sessions", 3);
+    println!(
+        "LRU after reading 'config': config={:?}, users={:?}, sessions={:?}",
+        lru.get(&"config").copied(),
+        lru.get(&"users").copied(),
+        lru.get(&"sessions").copied()
+    );
+
+    let tree = TreeNode::new(
+        "root",
+        vec![
+            TreeNode::new("left", vec![TreeNode::new("left.leaf", vec![])]),
+            TreeNode::new("right", vec![]),
+        ],
+    );
+    let mut visited = Vec::new();
+    tree.dfs(&mut |value| visited.push(*value));
+    println!("DFS order: {:?}", visited);
+
+    let mut graph = Graph::new();
+    for (from, to) in [(1, 2), (1, 3), (2, 4), (3, 4), (4, 5)] {
+        graph.add_edge(from, to);
+    }
+    println!("BFS order from 1: {:?}", graph.bfs(1));
+
+    let mut moving = MovingSum::new(3);
+    let sums: Vec<i64> = [4, 8, 15, 16, 23, 42]
+        .into_iter()
+        .map(|value| {
+            moving.push(value);
+            moving.current()
+        })
+        .collect();
+    println!("Moving sums (last 3): {:?}", sums);
+
+    let matrix = vec![vec![1, 2, 3], vec![4, 5, 6]];
+    println!("Transposed {:?} -> {:?}", matrix, transpose(&matrix));
+
+    SectionResult::ok("Collections", format!("{} entries", hashmap.len()))
+}
+
+fn format_table(rows: &[(String, i32)]) -> String {
+    let width = rows.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    rows.iter()
+        .map(|(key, value)| format!("{:<width$} : {}\n", key, value))
+        .collect()
+}
+
+fn report_table(out: &mut impl std::io::Write, rows: &[(String, i32)]) -> std::io::Result<()> {
+    out.write_all(format_table(rows).as_bytes())
+}
+
+fn print_table(rows: &[(String, i32)]) -> std::io::Result<()> {
+    report_table(&mut std::io::stdout(), rows)
+}
+
+fn value_counts<T: Eq + std::hash::Hash + Clone>(items: &[T]) -> HashMap<T, usize> {
+    let mut counts = HashMap::new();
+    for item in items {
+        *counts.entry(item.clone()).or_insert(0) += 1;
+    }
+    counts
+}
+
+struct TaskQueue {
+    heap: BinaryHeap<(i32, String)>,
+}
+
+impl TaskQueue {
+    fn new() -> Self {
+        TaskQueue {
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    fn push(&mut self, priority: i32, name: &str) {
+        self.heap.push((priority, name.to_string()));
+    }
+
+    fn pop(&mut self) -> Option<String> {
+        self.heap.pop().map(|(_, name)| name)
+    }
+}
+
+fn remove_unordered<T>(items: &mut Vec<T>, index: usize) -> Option<T> {
+    if index < items.len() {
+        Some(items.swap_remove(index))
+    } else {
+        None
+    }
+}
+
+struct BiMap<K, V> {
+    fwd: HashMap<K, V>,
+    rev: HashMap<V, K>,
+}
+
+impl<K: Eq + std::hash::Hash + Clone, V: Eq + std::hash::Hash + Clone> BiMap<K, V> {
+    fn new() -> Self {
+        BiMap {
+            fwd: HashMap::new(),
+            rev: HashMap::new(),
+        }
+    }
+
+    // Drops any existing pairing of either `key` or `value` so both maps stay one-to-one.
+    fn insert(&mut self, key: K, value: V) {
+        if let Some(old_value) = self.fwd.remove(&key) {
+            self.rev.remove(&old_value);
+        }
+        if let Some(old_key) = self.rev.remove(&value) {
+            self.fwd.remove(&old_key);
+        }
+        self.fwd.insert(key.clone(), value.clone());
+        self.rev.insert(value, key);
+    }
+
+    fn get_by_key(&self, key: &K) -> Option<&V> {
+        self.fwd.get(key)
+    }
+
+    fn get_by_value(&self, value: &V) -> Option<&K> {
+        self.rev.get(value)
+    }
+}
+
+// Tokens without an `=` are skipped; only the first `=` separates key from value.
+fn parse_kv(input: &str) -> HashMap<String, String> {
+    input
+        .split_whitespace()
+        .filter_map(|token| token.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+// Keys are ordered least- to most-recently used; reordering is O(n), fine for small caches.
+struct LruCache<K, V> {
+    capacity: usize,
+    map: HashMap<K, V>,
+    order: VecDeque<K>,
+}
+
+impl<K: Eq + std::hash::Hash + Clone, V> LruCache<K, V> {
+    fn new(capacity: usize) -> Self {
+        LruCache {
+            capacity,
+            map: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn touch(&mut self, key: &K) {
+        if let Some(k) = self
+            .order
+            .iter()
+            .position(|k| k == key)
+            .and_then(|position| self.order.remove(position))
+        {
+            self.order.push_back(k);
+        }
+    }
+
+    fn get(&mut self, key: &K) -> Option<&V> {
+        if self.map.contains_key(key) {
+            self.touch(key);
+        }
+        self.map.get(key)
+    }
+
+    fn put(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.map.insert(key.clone(), value).is_some() {
+            self.touch(&key);
+            return;
+        }
+        self.order.push_back(key);
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.map.remove(&oldest);
+            }
+        }
+    }
+}
+
+struct TreeNode<T> {
+    value: T,
+    children: Vec<TreeNode<T>>,
+}
+
+impl<T> TreeNode<T> {
+    fn new(value: T, children: Vec<TreeNode<T>>) -> Self {
+        TreeNode { value, children }
+    }
+
+    // Pre-order: each node is visited before its children, left to right.
+    fn dfs(&self, visit: &mut impl FnMut(&T)) {
+        visit(&self.value);
+        for child in &self.children {
+            child.dfs(visit);
+        }
+    }
+}
+
+// Directed graph stored as an adjacency list.
+struct Graph {
+    adj: HashMap<u32, Vec<u32>>,
+}
+
+impl Graph {
+    fn new() -> Self {
+        Graph {
+            adj: HashMap::new(),
+        }
+    }
+
+    fn add_edge(&mut self, from: u32, to: u32) {
+        self.adj.entry(from).or_default().push(to);
+    }
+
+    // Neighbours are visited in the order their edges were added.
+    fn bfs(&self, start: u32) -> Vec<u32> {
+        let mut order = Vec::new();
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &next in self.adj.get(&node).into_iter().flatten() {
+                if visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        order
+    }
+}
+
+// Sum of the most recent `capacity` values, updated in O(1) per push.
+struct MovingSum {
+    window: VecDeque<i64>,
+    capacity: usize,
+    sum: i64,
+}
+
+impl MovingSum {
+    fn new(capacity: usize) -> Self {
+        MovingSum {
+            window: VecDeque::with_capacity(capacity),
+            capacity,
+            sum: 0,
+        }
+    }
+
+    fn push(&mut self, value: i64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.window.len() == self.capacity {
+            if let Some(oldest) = self.window.pop_front() {
+                self.sum -= oldest;
+            }
+        }
+        self.window.push_back(value);
+        self.sum += value;
+    }
+
+    fn current(&self) -> i64 {
+        self.sum
+    }
+}
+
+// Same result as `vec![item; n]`, spelled out as a generic function.
+fn repeat_vec<T: Clone>(item: T, n: usize) -> Vec<T> {
+    std::iter::repeat_n(item, n).collect()
+}
+
+// Returns an empty matrix when the rows have differing lengths.
+fn transpose<T: Clone>(matrix: &[Vec<T>]) -> Vec<Vec<T>> {
+    let cols = match matrix.first() {
+        Some(row) => row.len(),
+        None => return Vec::new(),
+    };
+    if matrix.iter().any(|row| row.len() != cols) {
+        return Vec::new();
+    }
+    (0..cols)
+        .map(|col| matrix.iter().map(|row| row[col].clone()).collect())
+        .collect()
+}
+
+// Largest n whose Fibonacci number still fits in a u64.
+const FIB_MAX_N: u64 = 93;
+
+// Panics if `n > FIB_MAX_N`, since the result would overflow `u64`.
+fn fib_memo(n: u64) -> u64 {
+    assert!(n <= FIB_MAX_N, "fib_memo({}) overflows u64", n);
+    fib_cached(n, &mut HashMap::new())
+}
+
+fn fib_cached(n: u64, cache: &mut HashMap<u64, u64>) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    if let Some(&value) = cache.get(&n) {
+        return value;
+    }
+    let value = fib_cached(n - 1, cache)
+        .checked_add(fib_cached(n - 2, cache))
+        .expect("fib_memo overflowed u64");
+    cache.insert(n, value);
+    value
+}
+
+// Section 10: Macros
+macro_rules! custom_macro {
+    ($msg:expr) => {
+        println!("Custom macro says: {}", $msg);
+    };
+}
+
+macro_rules! timed {
+    ($label:expr, $body:block) => {{
+        let start = ::std::time::Instant::now();
+        let value = $body;
+        println!("{} took {:?}", $label, start.elapsed());
+        value
+    }};
+}
+
+fn macros_demo() -> SectionResult {
+    println!("\n--- Macros ---");
+    custom_macro!("Hello from a macro!");
+
+    let total = timed!("summing a million numbers", {
+        let mut sum: u64 = 0;
+        for i in 0..1_000_000u64 {
+            sum += i;
+        }
+        sum
+    });
+    println!("Timed sum: {}", total);
+
+    SectionResult::ok("Macros", "macro expanded")
+}
+
+// Section 11: Command-Line Arguments
+fn command_line_demo() -> SectionResult {
+    println!("\n--- Command-Line Arguments ---");
+
+    let config = global_config();
+    if !config.args.is_empty() {
+        println!("Arguments: {:?}", config.args);
+        match sum_cli_numbers(&config.args) {
+            Ok(total) => println!("Sum of arguments: {}", total),
+            Err(e) => println!("Could not sum arguments: {}", e),
+        }
+    } else {
+        println!("No arguments provided.");
+    }
+    println!("Verbose: {}", config.verbose);
+
+    let mut settings = Settings::new();
+    settings.set(SettingKey::Verbose, config.verbose.to_string());
+    settings.set(SettingKey::Threads, "8");
+    for key in [SettingKey::Verbose, SettingKey::Threads, SettingKey::Name] {
+        println!("Setting {:?} = {}", key, settings.get(key));
+    }
+
+    if config.stdin {
+        println!("Reading from stdin until EOF...");
+        match read_stdin_lines() {
+            Ok(lines) => println!("Read {} lines: {:?}", lines.len(), lines),
+            Err(e) => println!("Error reading stdin: {}", e),
+        }
+    }
+
+    if config.repl {
+        println!("Starting REPL; type 'help' for commands or 'quit' to exit.");
+        if let Err(e) = repl() {
+            println!("REPL error: {}", e);
+        }
+    }
+
+    let mut history = History::new(3);
+    for cmd in ["build", "test", "lint", "run"] {
+        history.record(cmd);
+    }
+    println!("Recent commands: {:?}", history.entries);
+
+    SectionResult::ok(
+        "Command-Line Arguments",
+        format!("{} arguments", config.args.len()),
+    )
+}
+
+struct History {
+    entries: VecDeque<String>,
+    max: usize,
+}
+
+impl History {
+    fn new(max: usize) -> Self {
+        History {
+            entries: VecDeque::with_capacity(max),
+            max,
+        }
+    }
+
+    fn record(&mut self, cmd: &str) {
+        if self.max == 0 {
+            return;
+        }
+        if self.entries.len() == self.max {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(cmd.to_string());
+    }
+}
+
+fn read_stdin_lines() -> Result<Vec<String>, std::io::Error> {
+    read_lines(std::io::stdin().lock())
+}
+
+fn read_lines(reader: impl std::io::BufRead) -> Result<Vec<String>, std::io::Error> {
+    reader.lines().collect()
+}
+
+// Handles one REPL command line; errors are reported back to the user.
+fn dispatch(line: &str) -> Result<String, String> {
+    let mut words = line.split_whitespace();
+    let args: Vec<String> = words.clone().skip(1).map(String::from).collect();
+    match words.next() {
+        None => Ok(String::new()),
+        Some("help") => Ok(String::from(
+            "commands: echo <text>, sum <n>..., help, quit",
+        )),
+        Some("echo") => Ok(args.join(" ")),
+        Some("sum") => sum_cli_numbers(&args)
+            .map(|total| total.to_string())
+            .map_err(|e| e.to_string()),
+        Some(other) => Err(format!("unknown command: {}", other)),
+    }
+}
+
+fn repl() -> Result<(), std::io::Error> {
+    run_repl(std::io::stdin().lock(), &mut std::io::stdout())
+}
+
+// Stops on `quit` or at end of input.
+fn run_repl(
+    input: impl std::io::BufRead,
+    out: &mut impl std::io::Write,
+) -> Result<(), std::io::Error> {
+    for line in input.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line == "quit" {
+            writeln!(out, "bye")?;
+            break;
+        }
+        match dispatch(line) {
+            Ok(reply) if reply.is_empty() => {}
+            Ok(reply) => writeln!(out, "{}", reply)?,
+            Err(e) => writeln!(out, "error: {}", e)?,
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+enum SumError {
+    #[error("not an integer: {0}")]
+    Parse(#[from] std::num::ParseIntError),
+    #[error("sum overflows i64")]
+    Overflow,
+}
+
+fn sum_cli_numbers(args: &[String]) -> Result<i64, SumError> {
+    let mut total: i64 = 0;
+    for arg in args {
+        total = total
+            .checked_add(arg.parse::<i64>()?)
+            .ok_or(SumError::Overflow)?;
+    }
+    Ok(total)
+}
+
+// Section 12: Panic Recovery
+fn panic_recovery_demo() -> SectionResult {
+    println!("\n--- Panic Recovery ---");
+
+    let result = run_section("Deliberate Panic", || panic!("something went wrong"));
+    println!(
+        "Section '{}' success: {}, detail: {}",
+        result.name, result.success, result.detail
+    );
+
+    if result.success {
+        SectionResult::failed("Panic Recovery", "panic was not caught")
+    } else {
+        SectionResult::ok("Panic Recovery", "panic caught and reported")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx_eq(a: f64, b: f64, epsilon: f64) -> bool {
+        (a - b).abs() <= epsilon
+    }
+
+    #[test]
+    fn test_calculate_length() {
+        let test_string = String::from("Rust");
+        assert_eq!(calculate_length(&test_string), 4);
+    }
+
+    #[test]
+    fn test_circle_area() {
+        let circle = Circle { radius: 2.0 };
+        assert!(approx_eq(circle.area(), 12.56636, 1e-9));
+    }
+
+    #[test]
+    fn test_zip_with_equal_lengths() {
+        let sums = zip_with(&[1, 2, 3], &[4, 5, 6], |a, b| a + b);
+        assert_eq!(sums, vec![5, 7, 9]);
+    }
+
+    #[test]
+    fn test_zip_with_stops_at_shorter() {
+        let products = zip_with(&[1, 2, 3, 4], &[10, 20], |a, b| a * b);
+        assert_eq!(products, vec![10, 40]);
+    }
+
+    #[test]
+    fn test_router_dispatches_per_variant() {
+        let log = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let hello_log = std::rc::Rc::clone(&log);
+        let quit_log = std::rc::Rc::clone(&log);
+        let router = Router {
+            on_hello: Box::new(move |msg| hello_log.borrow_mut().push(format!("hello {}", msg))),
+            on_quit: Box::new(move || quit_log.borrow_mut().push(String::from("quit"))),
+        };
+
+        router.route(&Message::Hello(String::from("Rust")));
+        router.route(&Message::Quit);
+
+        assert_eq!(*log.borrow(), vec!["hello Rust", "quit"]);
+    }
+
+    #[test]
+    fn test_format_summary() {
+        let results = vec![
+            SectionResult::ok("Macros", "macro expanded"),
+            SectionResult::failed("Async Programming", "runtime error"),
+        ];
+        let expected = "\
+Section            Status  Detail
+Macros             OK      macro expanded
+Async Programming  FAILED  runtime error
+";
+        assert_eq!(format_summary(&results), expected);
+    }
+
+    #[test]
+    fn test_run_after_runs_callback() {
+        let fired = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&fired);
+        let handle = run_after(Duration::from_millis(10), move || {
+            flag.store(true, Ordering::SeqCst);
+        });
+
+        handle.join().unwrap();
+        assert!(fired.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_circle_bounding_box() {
+        let circle = Circle { radius: 3.0 };
+        assert_eq!(circle.bounding_box(), (6.0, 6.0));
+    }
+
+    #[test]
+    fn test_rectangle_bounding_box() {
+        let rectangle = Rectangle {
+            width: 2.0,
+            height: 5.0,
+        };
+        assert_eq!(rectangle.bounding_box(), (2.0, 5.0));
+    }
+
+    #[test]
+    fn test_run_section_catches_panic() {
+        let result = run_section("Boom", || panic!("boom"));
+        assert!(!result.success);
+        assert_eq!(result.name, "Boom");
+        assert_eq!(result.detail, "panicked: boom");
+    }
+
+    #[test]
+    fn test_global_config_is_initialized_once() {
+        assert!(std::ptr::eq(global_config(), global_config()));
+
+        let cell = OnceLock::new();
+        let loads = std::cell::Cell::new(0);
+        let load = || {
+            loads.set(loads.get() + 1);
+            Config {
+                verbose: true,
+                async_io: false,
+                stdin: false,
+                repl: false,
+                args: vec![String::from("first")],
+            }
+        };
+
+        let first = init_once(&cell, load);
+        let second = init_once(&cell, || panic!("config loaded twice"));
+        assert!(std::ptr::eq(first, second));
+        assert_eq!(second.args, vec!["first"]);
+        assert_eq!(loads.get(), 1);
+    }
+
+    #[test]
+    fn test_celsius_to_fahrenheit() {
+        assert_eq!(celsius_to_fahrenheit(0.0), 32.0);
+        assert_eq!(celsius_to_fahrenheit(100.0), 212.0);
+        assert_eq!(convert_all(&[0.0, 100.0]), vec![32.0, 212.0]);
+    }
+
+    #[test]
+    fn test_temperature_round_trip() {
+        for c in [-40.0, 0.0, 21.5, 100.0] {
+            let back = fahrenheit_to_celsius(celsius_to_fahrenheit(c));
+            assert!(approx_eq(back, c, 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_flatten() {
+        let nested = vec![vec![1, 2], vec![3], vec![4, 5]];
+        assert_eq!(flatten(&nested), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_flatten_skips_empty() {
+        let nested = vec![vec![1], vec![], vec![2, 3]];
+        assert_eq!(flatten(&nested), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_subject_broadcasts_to_all_observers() {
+        struct RecordingObserver {
+            id: u32,
+            log: std::rc::Rc<std::cell::RefCell<Vec<String>>>,
+        }
+
+        impl Observer for RecordingObserver {
+            fn notify(&self, event: &str) {
+                self.log
+                    .borrow_mut()
+                    .push(format!("{}: {}", self.id, event));
+            }
+        }
+
+        let log = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let mut subject = Subject::new();
+        for id in 1..=2 {
+            subject.subscribe(Box::new(RecordingObserver {
+                id,
+                log: std::rc::Rc::clone(&log),
+            }));
+        }
+
+        subject.broadcast("ping");
+        assert_eq!(*log.borrow(), vec!["1: ping", "2: ping"]);
+    }
+
+    #[tokio::test]
+    async fn test_collect_stream() {
+        assert_eq!(collect_stream(4).await, vec![0, 1, 2, 3]);
+        assert!(collect_stream(0).await.is_empty());
+    }
+
+    #[test]
+    fn test_factorial() {
+        assert_eq!(factorial(0), Some(1));
+        assert_eq!(factorial(5), Some(120));
+        assert_eq!(factorial(20), Some(2_432_902_008_176_640_000));
+    }
+
+    #[test]
+    fn test_factorial_overflow() {
+        assert_eq!(factorial(21), None);
+        assert_eq!(factorial(25), None);
+    }
+
+    #[test]
+    fn test_normalize_borrows_clean_input() {
+        let result = normalize("clean text");
+        assert!(matches!(result, std::borrow::Cow::Borrowed("clean text")));
+    }
+
+    #[test]
+    fn test_normalize_owns_dirty_input() {
+        let result = normalize("  Dirty Text ");
+        assert!(matches!(result, std::borrow::Cow::Owned(_)));
+        assert_eq!(result, "dirty text");
+    }
+
+    #[test]
+    fn test_stack_iter_items() {
+        let mut stack = Stack::new();
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+
+        let items: Vec<i32> = stack.iter_items().copied().collect();
+        assert_eq!(items, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_read_bytes_lossy_replaces_invalid_utf8() {
+        let path = env::temp_dir().join("rust_demo_test_lossy.txt");
+        std::fs::write(&path, b"ok \xC3\x28 end").unwrap();
+
+        let content = read_bytes_lossy(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(content.contains('\u{FFFD}'));
+        assert!(content.starts_with("ok "));
+        assert!(content.ends_with(" end"));
+    }
+
+    #[test]
+    fn test_circle_area_const_matches_trait() {
+        let circle = Circle { radius: 2.5 };
+        assert!(approx_eq(circle_area_const(2.5), circle.area(), 1e-12));
+        assert!(approx_eq(UNIT_CIRCLE_AREA, PI, 1e-12));
+    }
+
+    #[test]
+    fn test_registry_concurrent_increments() {
+        let registry = Arc::new(Registry::new());
+        registry.insert("seeded", 5);
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let registry = Arc::clone(&registry);
+                thread::spawn(move || {
+                    for _ in 0..100 {
+                        registry.increment("seeded");
+                        registry.increment("fresh");
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(registry.get("seeded"), Some(805));
+        assert_eq!(registry.get("fresh"), Some(800));
+        assert_eq!(registry.get("missing"), None);
+    }
+
+    #[test]
+    fn test_format_table_aligns_keys() {
+        let rows = vec![
+            (String::from("a"), 1),
+            (String::from("longer"), 20),
+            (String::from("mid"), 300),
+        ];
+        let expected = "a      : 1\nlonger : 20\nmid    : 300\n";
+        assert_eq!(format_table(&rows), expected);
+        assert_eq!(format_table(&[]), "");
+    }
+
+    #[test]
+    fn test_partition_even_odd() {
+        let (evens, odds) = partition(&[1, 2, 3, 4], |x| x % 2 == 0);
+        assert_eq!(evens, vec![2, 4]);
+        assert_eq!(odds, vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_debouncer_waits_for_quiet_period() {
+        let debouncer = Debouncer {
+            delay: Duration::from_millis(50),
+        };
+
+        let start = Instant::now();
+        let mut fired = false;
+        debouncer.call(start, || fired = true).await;
+        assert!(fired);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        let stale = Instant::now() - Duration::from_millis(100);
+        let before = Instant::now();
+        debouncer.call(stale, || {}).await;
+        assert!(before.elapsed() < Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_complex_add() {
+        let sum = Complex { re: 1.0, im: 2.0 } + Complex { re: 3.0, im: -5.0 };
+        assert_eq!(sum, Complex { re: 4.0, im: -3.0 });
+        assert_eq!(sum.to_string(), "4 - 3i");
+    }
+
+    #[test]
+    fn test_complex_mul() {
+        let product = Complex { re: 1.0, im: 2.0 } * Complex { re: 3.0, im: 4.0 };
+        assert_eq!(product, Complex { re: -5.0, im: 10.0 });
+        assert_eq!(product.to_string(), "-5 + 10i");
+    }
+
+    #[test]
+    fn test_walk_dir_finds_nested_files() {
+        let root = env::temp_dir().join("rust_demo_test_walk");
+        let deep = root.join("a").join("b");
+        std::fs::create_dir_all(&deep).unwrap();
+        std::fs::create_dir_all(root.join("empty")).unwrap();
+        std::fs::write(root.join("one.txt"), "1").unwrap();
+        std::fs::write(root.join("a").join("two.txt"), "2").unwrap();
+        std::fs::write(deep.join("three.txt"), "3").unwrap();
+
+        let files = walk_dir(root.to_str().unwrap()).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let mut expected = vec![
+            root.join("one.txt"),
+            root.join("a").join("two.txt"),
+            deep.join("three.txt"),
+        ]
+        .into_iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(files, expected);
+    }
+
+    #[test]
+    fn test_circle_debug_includes_units_and_area() {
+        let circle = Circle { radius: 2.0 };
+        let debug = format!("{:?}", circle);
+        assert_eq!(debug, "Circle { radius: 2 units, area: 12.57 }");
+    }
+
+    #[test]
+    fn test_blocking_queue_respects_capacity() {
+        let queue = Arc::new(BlockingQueue::new(2));
+        queue.push(1);
+        queue.push(2);
+
+        let producer_queue = Arc::clone(&queue);
+        let producer = thread::spawn(move || producer_queue.push(3));
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(queue.len(), 2);
+
+        assert_eq!(queue.pop(), 1);
+        producer.join().unwrap();
+        assert_eq!(queue.pop(), 2);
+        assert_eq!(queue.pop(), 3);
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn test_traffic_light_cycle() {
+        let green = TrafficLight::Red.next();
+        assert_eq!(green, TrafficLight::Green);
+        let yellow = green.next();
+        assert_eq!(yellow, TrafficLight::Yellow);
+        assert_eq!(yellow.next(), TrafficLight::Red);
+    }
+
+    #[tokio::test]
+    async fn test_async_task_verbosity() {
+        let log = async_task(true, Duration::from_millis(10)).await;
+        assert_eq!(log, vec!["Async task started...", "Async task finished!"]);
+
+        let quiet = async_task(false, Duration::from_millis(10)).await;
+        assert!(quiet.is_empty());
+    }
+
+    #[test]
+    fn test_value_counts() {
+        let counts = value_counts(&["a", "b", "a", "c", "a", "b"]);
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts["a"], 3);
+        assert_eq!(counts["b"], 2);
+        assert_eq!(counts["c"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancellable_work_completes() {
+        let (_cancel_tx, cancel_rx) = tokio::sync::oneshot::channel::<()>();
+        assert_eq!(cancellable_work(cancel_rx).await, "completed");
+    }
+
+    #[tokio::test]
+    async fn test_cancellable_work_cancelled() {
+        let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel();
+        cancel_tx.send(()).unwrap();
+
+        let start = Instant::now();
+        assert_eq!(cancellable_work(cancel_rx).await, "cancelled");
+        assert!(start.elapsed() < LONG_WORK_DURATION);
+    }
+
+    #[test]
+    fn test_rotate_left() {
+        assert_eq!(rotate_left(&[1, 2, 3, 4, 5], 2), vec![3, 4, 5, 1, 2]);
+        assert_eq!(rotate_left(&[1, 2, 3], 0), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_rotate_left_wraps_large_n() {
+        assert_eq!(rotate_left(&[1, 2, 3], 7), vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn test_rotate_left_empty() {
+        let empty: [i32; 0] = [];
+        assert!(rotate_left(&empty, 3).is_empty());
+    }
+
+    #[test]
+    fn test_fib_memo() {
+        assert_eq!(fib_memo(0), 0);
+        assert_eq!(fib_memo(1), 1);
+        assert_eq!(fib_memo(2), 1);
+        assert_eq!(fib_memo(10), 55);
+        assert_eq!(fib_memo(FIB_MAX_N), 12_200_160_415_121_876_738);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows u64")]
+    fn test_fib_memo_rejects_overflow() {
+        fib_memo(FIB_MAX_N + 1);
+    }
+
+    #[test]
+    fn test_index_of_largest() {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Circle { radius: 1.0 }),
+            Box::new(Rectangle {
+                width: 10.0,
+                height: 1.0,
+            }),
+            Box::new(Circle { radius: 0.5 }),
+        ];
+        assert_eq!(index_of_largest(&shapes), Some(1));
+        assert_eq!(index_of_largest(&[]), None);
+
+        let circle = Circle { radius: 2.0 };
+        assert_eq!(as_shape(&circle).area(), circle.area());
+    }
+
+    #[test]
+    fn test_read_config_file_sync() {
+        let path = env::temp_dir().join("rust_demo_test_config_sync.txt");
+        std::fs::write(&path, "threads=4").unwrap();
+
+        let content = read_config_file(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(content.unwrap(), "threads=4");
+    }
+
+    #[tokio::test]
+    async fn test_read_config_file_async_matches_sync() {
+        let path = env::temp_dir().join("rust_demo_test_config_async.txt");
+        std::fs::write(&path, "name=demo\nverbose=1").unwrap();
+        let path_str = path.to_str().unwrap();
+
+        let sync_content = read_config_file(path_str).unwrap();
+        let async_content = read_config_file_async(path_str).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(sync_content, async_content);
+    }
+
+    #[test]
+    fn test_window_sums() {
+        assert_eq!(window_sums(&[1, 2, 3, 4], 2), vec![3, 5, 7]);
+        assert_eq!(window_sums(&[1, 2, 3, 4], 4), vec![10]);
+    }
+
+    #[test]
+    fn test_window_sums_zero_window() {
+        assert!(window_sums(&[1, 2, 3], 0).is_empty());
+    }
+
+    #[test]
+    fn test_window_sums_window_too_large() {
+        assert!(window_sums(&[1, 2, 3], 4).is_empty());
+    }
+
+    #[test]
+    fn test_task_queue_pops_by_priority() {
+        let mut tasks = TaskQueue::new();
+        tasks.push(2, "medium");
+        tasks.push(10, "urgent");
+        tasks.push(-1, "someday");
+        tasks.push(5, "soon");
+
+        assert_eq!(tasks.pop().as_deref(), Some("urgent"));
+        assert_eq!(tasks.pop().as_deref(), Some("soon"));
+        assert_eq!(tasks.pop().as_deref(), Some("medium"));
+        assert_eq!(tasks.pop().as_deref(), Some("someday"));
+        assert_eq!(tasks.pop(), None);
+    }
+
+    #[test]
+    fn test_make_adder() {
+        assert_eq!(make_adder(5)(10), 15);
+    }
+
+    #[test]
+    fn test_make_adder_closures_are_independent() {
+        let add_one = make_adder(1);
+        let add_hundred = make_adder(100);
+        assert_eq!(add_one(0), 1);
+        assert_eq!(add_hundred(0), 100);
+        assert_eq!(add_one(add_hundred(1)), 102);
+    }
+
+    #[test]
+    fn test_find_first_match() {
+        assert_eq!(find_first(&[1, 5, 3, 7], |&x| x > 2), Some(&5));
+    }
+
+    #[test]
+    fn test_find_first_no_match() {
+        assert_eq!(find_first(&[1, 2], |&x| x > 2), None);
+    }
+
+    #[tokio::test]
+    async fn test_ticker_counts_ticks() {
+        let start = Instant::now();
+        assert_eq!(ticker(Duration::from_millis(20), 5).await, 5);
+
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(80));
+        assert!(elapsed < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_apply_ops() {
+        assert_eq!(apply(Op::Add, 6.0, 3.0), Ok(9.0));
+        assert_eq!(apply(Op::Sub, 6.0, 3.0), Ok(3.0));
+        assert_eq!(apply(Op::Mul, 6.0, 3.0), Ok(18.0));
+        assert_eq!(apply(Op::Div, 6.0, 3.0), Ok(2.0));
+    }
+
+    #[test]
+    fn test_apply_divide_by_zero() {
+        assert_eq!(
+            apply(Op::Div, 1.0, 0.0),
+            Err(String::from("division by zero"))
+        );
+    }
+
+    #[test]
+    fn test_remove_unordered_in_bounds() {
+        let mut items = vec![1, 2, 3, 4];
+        assert_eq!(remove_unordered(&mut items, 1), Some(2));
+        assert_eq!(items, vec![1, 4, 3]);
+    }
+
+    #[test]
+    fn test_remove_unordered_out_of_bounds() {
+        let mut items = vec![1, 2];
+        assert_eq!(remove_unordered(&mut items, 2), None);
+        assert_eq!(items, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_sum_cli_numbers() {
+        let args = vec![String::from("4"), String::from("-1"), String::from("10")];
+        assert_eq!(sum_cli_numbers(&args), Ok(13));
+    }
+
+    #[test]
+    fn test_sum_cli_numbers_rejects_non_numeric() {
+        let args = vec![String::from("4"), String::from("four")];
+        assert!(sum_cli_numbers(&args).is_err());
+    }
+
+    #[test]
+    fn test_draw_circle_into_string() {
+        let mut out = String::new();
+        Circle { radius: 1.5 }.draw(&mut out);
+        assert_eq!(out, "( circle r=1.5 )\n");
+    }
+
+    #[tokio::test]
+    async fn test_run_all_returns_every_index() {
+        let delays = vec![
+            Duration::from_millis(30),
+            Duration::from_millis(10),
+            Duration::from_millis(20),
+        ];
+        let mut indices = run_all(delays).await;
+        indices.sort();
+        assert_eq!(indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_transpose_square() {
+        let matrix = vec![vec![1, 2], vec![3, 4]];
+        assert_eq!(transpose(&matrix), vec![vec![1, 3], vec![2, 4]]);
+    }
+
+    #[test]
+    fn test_transpose_non_square() {
+        let matrix = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        assert_eq!(transpose(&matrix), vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+    }
+
+    #[test]
+    fn test_transpose_empty_and_ragged() {
+        let empty: Vec<Vec<i32>> = Vec::new();
+        assert!(transpose(&empty).is_empty());
+
+        let ragged = vec![vec![1, 2], vec![3]];
+        assert!(transpose(&ragged).is_empty());
+    }
+
+    #[test]
+    fn test_lock_recover_after_poisoning() {
+        let shared = Arc::new(Mutex::new(1));
+        let poisoner = Arc::clone(&shared);
+        let result = thread::spawn(move || {
+            let mut guard = poisoner.lock().unwrap();
+            *guard = 2;
+            panic!("poisoning the lock");
+        })
+        .join();
+
+        assert!(result.is_err());
+        assert!(shared.is_poisoned());
+        let mut guard = lock_recover(&shared);
+        assert_eq!(*guard, 2);
+        *guard += 1;
+        assert_eq!(*guard, 3);
+    }
+
+    #[test]
+    fn test_history_drops_oldest_over_capacity() {
+        let mut history = History::new(2);
+        history.record("one");
+        history.record("two");
+        assert_eq!(history.entries, vec!["one", "two"]);
+
+        history.record("three");
+        assert_eq!(history.entries, vec!["two", "three"]);
+
+        let mut disabled = History::new(0);
+        disabled.record("ignored");
+        assert!(disabled.entries.is_empty());
+    }
+
+    #[test]
+    fn test_largest_shape() {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Rectangle {
+                width: 3.0,
+                height: 4.0,
+            }),
+            Box::new(Circle { radius: 2.0 }),
+            Box::new(Rectangle {
+                width: 1.0,
+                height: 1.0,
+            }),
+        ];
+        let largest = largest_shape(&shapes).unwrap();
+        assert!(approx_eq(
+            largest.area(),
+            Circle { radius: 2.0 }.area(),
+            1e-9
+        ));
+        assert!(largest_shape(&[]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_async_cache_computes_once_per_key() {
+        let cache = AsyncCache::new();
+        let computed = AtomicUsize::new(0);
+        let compute = |value: &'static str| {
+            let computed = &computed;
+            async move {
+                computed.fetch_add(1, Ordering::SeqCst);
+                value.to_string()
+            }
+        };
+
+        assert_eq!(cache.get_or_compute("a", compute("first")).await, "first");
+        assert_eq!(cache.get_or_compute("a", compute("second")).await, "first");
+        assert_eq!(cache.get_or_compute("b", compute("other")).await, "other");
+        assert_eq!(computed.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_exit_code_from_results() {
+        let passing = vec![
+            SectionResult::ok("Macros", "macro expanded"),
+            SectionResult::ok("Collections", "2 entries"),
+        ];
+        assert_eq!(exit_code(&passing), 0);
+        assert_eq!(exit_code(&[]), 0);
+
+        let mixed = vec![
+            SectionResult::ok("Macros", "macro expanded"),
+            SectionResult::failed("Async Programming", "runtime error"),
+        ];
+        assert_eq!(exit_code(&mixed), 1);
+    }
+
+    #[test]
+    fn test_interleave_equal_lengths() {
+        assert_eq!(interleave(&[1, 3], &[2, 4]), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_interleave_differing_lengths() {
+        assert_eq!(interleave(&[1, 3, 5], &[2, 4]), vec![1, 2, 3, 4, 5]);
+        assert_eq!(interleave(&[1], &[2, 4, 6]), vec![1, 2, 4, 6]);
+    }
+
+    // Compiles only if T can be shared across threads. Registry is handed to
+    // spawned threads behind an Arc, so a field that is not Send + Sync (an Rc,
+    // a RefCell) would break that sharing; this turns it into a build error here.
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_shared_types_are_send_sync() {
+        assert_send_sync::<Arc<Registry>>();
+        assert_send_sync::<Arc<Mutex<i32>>>();
+        assert_send_sync::<Arc<BlockingQueue<i32>>>();
+    }
+
+    #[test]
+    fn test_event_bus_chained_handlers_all_fire() {
+        let log = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let recorder = |tag: &'static str| {
+            let log = std::rc::Rc::clone(&log);
+            move |event: &str| log.borrow_mut().push(format!("{}:{}", tag, event))
+        };
+        let bus = EventBus::new()
+            .on(recorder("a"))
+            .on(recorder("b"))
+            .on(recorder("c"));
+
+        bus.emit("tick");
+        assert_eq!(*log.borrow(), vec!["a:tick", "b:tick", "c:tick"]);
+    }
+
+    #[test]
+    fn test_enumerate_from() {
+        let items = ['a', 'b', 'c'];
+        assert_eq!(
+            enumerate_from(&items, 1),
+            vec![(1, &'a'), (2, &'b'), (3, &'c')]
+        );
+        assert_eq!(enumerate_from(&items, 10)[0], (10, &'a'));
+    }
+
+    #[test]
+    fn test_demo_error_display() {
+        let io = DemoError::from(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "missing.txt not found",
+        ));
+        assert_eq!(io.to_string(), "I/O error: missing.txt not found");
+
+        let parse = parse_number("abc").unwrap_err();
+        assert_eq!(
+            parse.to_string(),
+            "parse error: invalid digit found in string"
+        );
+
+        let shape = validate_circle(-1.0).unwrap_err();
+        assert_eq!(
+            shape.to_string(),
+            "invalid shape: circle radius must be positive, got -1"
+        );
+    }
+
+    #[test]
+    fn test_compute_with_channel() {
+        assert_eq!(compute_with_channel(vec![1, -2, 3]), vec![1, 4, 9]);
+        assert!(compute_with_channel(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_clamp_all() {
+        assert_eq!(clamp_all(&[-1.0, 0.5, 2.0], 0.0, 1.0), vec![0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "requires lo <= hi")]
+    fn test_clamp_all_rejects_inverted_range() {
+        clamp_all(&[0.5], 1.0, 0.0);
+    }
+
+    #[test]
+    fn test_read_lines_from_cursor() {
+        let input = std::io::Cursor::new("first line\nsecond\n\nlast");
+        let lines = read_lines(input).unwrap();
+        assert_eq!(lines, vec!["first line", "second", "", "last"]);
+    }
+
+    #[test]
+    fn test_shape_stats() {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Rectangle {
+                width: 2.0,
+                height: 3.0,
+            }),
+            Box::new(Rectangle {
+                width: 1.0,
+                height: 1.0,
+            }),
+            Box::new(Circle { radius: 2.0 }),
+        ];
+        let (total, min, max) = shape_stats(&shapes);
+        let circle_area = Circle { radius: 2.0 }.area();
+        assert!(approx_eq(total, 7.0 + circle_area, 1e-9));
+        assert_eq!(min, 1.0);
+        assert!(approx_eq(max, circle_area, 1e-9));
+    }
+
+    #[test]
+    fn test_shape_stats_empty() {
+        assert_eq!(shape_stats(&[]), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_repeat_vec_empty() {
+        assert!(repeat_vec("x", 0).is_empty());
+    }
+
+    #[test]
+    fn test_repeat_vec_positive() {
+        assert_eq!(repeat_vec(0, 5), vec![0, 0, 0, 0, 0]);
+        assert_eq!(repeat_vec(String::from("ab"), 2), vec!["ab", "ab"]);
+    }
+
+    #[test]
+    fn test_spin_lock_prevents_lost_updates() {
+        let spin_lock = Arc::new(SpinLock::new());
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let spin_lock = Arc::clone(&spin_lock);
+                let counter = Arc::clone(&counter);
+                thread::spawn(move || {
+                    for _ in 0..1000 {
+                        spin_lock.with_lock(|| {
+                            let current = counter.load(Ordering::Relaxed);
+                            counter.store(current + 1, Ordering::Relaxed);
+                        });
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(counter.load(Ordering::Relaxed), 8000);
+    }
+
+    #[test]
+    fn test_dedup_adjacent() {
+        assert_eq!(dedup_adjacent(&[1, 1, 2, 3, 3, 3, 1]), vec![1, 2, 3, 1]);
+    }
+
+    #[test]
+    fn test_dedup_adjacent_without_duplicates() {
+        assert_eq!(dedup_adjacent(&["a", "b", "a"]), vec!["a", "b", "a"]);
+    }
+
+    #[test]
+    fn test_write_lines() {
+        let circles = [Circle { radius: 1.0 }, Circle { radius: 2.5 }];
+        assert_eq!(write_lines(&circles), "circle 1\ncircle 2.5");
+
+        let messages = [Message::Hello(String::from("Rust")), Message::Quit];
+        assert_eq!(write_lines(&messages), "hello Rust\nquit");
+
+        assert_eq!(Point { x: 3, y: -4 }.to_line(), "point 3 -4");
+        assert_eq!(write_lines::<Circle>(&[]), "");
+    }
+
+    #[test]
+    fn test_shutdown_guard_records_on_drop() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let _guard = ShutdownGuard {
+                name: String::from("worker"),
+                log: Arc::clone(&log),
+            };
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            assert!(log.lock().unwrap().is_empty());
+        });
+
+        assert_eq!(*log.lock().unwrap(), vec!["worker shut down"]);
+    }
+
+    #[test]
+    fn test_bimap_lookups() {
+        let mut map = BiMap::new();
+        map.insert("alice", 1);
+        map.insert("bob", 2);
+
+        assert_eq!(map.get_by_key(&"alice"), Some(&1));
+        assert_eq!(map.get_by_value(&2), Some(&"bob"));
+        assert_eq!(map.get_by_key(&"carol"), None);
+    }
+
+    #[test]
+    fn test_bimap_overwrite_keeps_reverse_consistent() {
+        let mut map = BiMap::new();
+        map.insert("alice", 1);
+        map.insert("alice", 3);
+
+        assert_eq!(map.get_by_key(&"alice"), Some(&3));
+        assert_eq!(map.get_by_value(&3), Some(&"alice"));
+        assert_eq!(map.get_by_value(&1), None);
+
+        map.insert("bob", 3);
+        assert_eq!(map.get_by_key(&"alice"), None);
+        assert_eq!(map.get_by_value(&3), Some(&"bob"));
+    }
+
+    #[test]
+    fn test_panic_hook_records_thread_panic() {
+        // Panic hooks are process-wide, so put the original back before
+        // asserting rather than leaving the recording hook for other tests.
+        let original = std::panic::take_hook();
+        let recorded = install_panic_hook();
+        let result = thread::spawn(|| panic!("hooked panic 42")).join();
+        drop(std::panic::take_hook());
+        std::panic::set_hook(original);
+
+        assert!(result.is_err());
+        assert!(lock_recover(&recorded)
+            .iter()
+            .any(|msg| msg == "hooked panic 42"));
+    }
+
+    #[test]
+    fn test_smallest_perimeter() {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Circle { radius: 1.0 }),
+            Box::new(Rectangle {
+                width: 1.0,
+                height: 1.0,
+            }),
+            Box::new(Rectangle {
+                width: 5.0,
+                height: 2.0,
+            }),
+        ];
+        assert_eq!(smallest_perimeter(&shapes), Some(4.0));
+        assert_eq!(smallest_perimeter(&[]), None);
+    }
+
+    #[test]
+    fn test_tokenize_spans() {
+        let tokens = tokenize("  hi there\tzoë ");
+        assert_eq!(
+            tokens,
+            vec![
+                (2, 4, String::from("hi")),
+                (5, 10, String::from("there")),
+                (11, 15, String::from("zoë")),
+            ]
+        );
+        assert!(tokenize("   ").is_empty());
+    }
+
+    #[test]
+    fn test_parse_kv_drops_malformed_tokens() {
+        let map = parse_kv("a=1 b=2 bad url=x=y");
+        assert_eq!(map.len(), 3);
+        assert_eq!(map["a"], "1");
+        assert_eq!(map["b"], "2");
+        assert_eq!(map["url"], "x=y");
+        assert!(!map.contains_key("bad"));
+    }
+
+    #[test]
+    fn test_retry_sync_succeeds_after_failures() {
+        let mut calls = 0;
+        let result = retry_sync(5, || {
+            calls += 1;
+            if calls <= 2 {
+                Err("not yet")
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result, Ok(3));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_retry_sync_gives_up() {
+        let mut calls = 0;
+        let result: Result<(), &str> = retry_sync(2, || {
+            calls += 1;
+            Err("always")
+        });
+        assert_eq!(result, Err("always"));
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn test_pipeline_runs_stages_in_order() {
+        let stages: Vec<Stage> = vec![Box::new(|x| x + 1), Box::new(|x| x * 2)];
+        assert_eq!(pipeline(stages, 3), 8);
+    }
+
+    #[test]
+    fn test_pipeline_empty_returns_input() {
+        assert_eq!(pipeline(Vec::new(), 7), 7);
+    }
+
+    #[test]
+    fn test_throughput_meter_rate() {
+        let mut meter = ThroughputMeter::new();
+        for _ in 0..100 {
+            meter.record();
+        }
+        thread::sleep(Duration::from_millis(100));
+
+        // 100 events over at least 100ms caps the rate at 1000/s; allow for slow CI.
+        let rate = meter.per_second();
+        assert!(rate <= 1000.0, "rate too high: {}", rate);
+        assert!(rate > 100.0, "rate too low: {}", rate);
+    }
+
+    #[test]
+    fn test_safe_divide() {
+        assert_eq!(safe_divide(&[2.0, 5.0], 2.0), Ok(vec![1.0, 2.5]));
+    }
+
+    #[test]
+    fn test_safe_divide_by_zero() {
+        assert_eq!(
+            safe_divide(&[1.0], 0.0),
+            Err(String::from("division by zero"))
+        );
+    }
+
+    #[test]
+    fn test_settings_get_set() {
+        let mut settings = Settings::new();
+        settings.set(SettingKey::Threads, "16");
+        settings.set(SettingKey::Name, String::from("custom"));
+
+        assert_eq!(settings.get(SettingKey::Threads), "16");
+        assert_eq!(settings.get(SettingKey::Name), "custom");
+    }
+
+    #[test]
+    fn test_settings_default_for_unset_key() {
+        let settings = Settings::new();
+        assert_eq!(settings.get(SettingKey::Verbose), "true");
+        assert_eq!(settings.get(SettingKey::Threads), "4");
+    }
+
+    #[test]
+    fn test_parallel_total_area_matches_sequential() {
+        let make_shapes = || -> Vec<Box<dyn Shape + Send>> {
+            vec![
+                Box::new(Circle { radius: 1.0 }),
+                Box::new(Rectangle {
+                    width: 2.0,
+                    height: 3.0,
+                }),
+                Box::new(Circle { radius: 2.5 }),
+                Box::new(Rectangle {
+                    width: 4.0,
+                    height: 0.5,
+                }),
+                Box::new(Circle { radius: 0.1 }),
+            ]
+        };
+        let sequential = total_area(&make_shapes());
+
+        for threads in [0, 1, 2, 3, 8] {
+            let parallel = parallel_total_area(make_shapes(), threads);
+            assert!(approx_eq(parallel, sequential, 1e-9));
+        }
+        assert_eq!(parallel_total_area(Vec::new(), 4), 0.0);
+    }
+
+    #[test]
+    fn test_timed_returns_block_value() {
+        let value = timed!("test block", { (1..=4).product::<i32>() });
+        assert_eq!(value, 24);
+    }
+
+    #[test]
+    fn test_argmax() {
+        assert_eq!(argmax(&[1, 7, 3]), Some(1));
+        assert_eq!(argmax(&[0.5, -1.0, 2.5]), Some(2));
+    }
+
+    #[test]
+    fn test_argmax_tie_returns_first() {
+        assert_eq!(argmax(&[4, 9, 2, 9]), Some(1));
+    }
+
+    #[test]
+    fn test_argmax_empty() {
+        let empty: [i32; 0] = [];
+        assert_eq!(argmax(&empty), None);
+    }
+
+    #[test]
+    fn test_lru_cache_keeps_recently_read_key() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        assert_eq!(cache.get(&"a"), Some(&1));
+
+        // FIFO would evict "a" here; LRU evicts "b" because "a" was just read.
+        cache.put("c", 3);
+        assert_eq!(cache.get(&"a"), Some(&1));
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"c"), Some(&3));
+    }
+
+    #[test]
+    fn test_lru_cache_overwrite_refreshes_recency() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("a", 10);
+        cache.put("c", 3);
+
+        assert_eq!(cache.get(&"a"), Some(&10));
+        assert_eq!(cache.get(&"b"), None);
+    }
+
+    #[test]
+    fn test_sum_until_negative_stops_early() {
+        assert_eq!(sum_until_negative(&[1, 2, -1, 3]), 3);
+        assert_eq!(sum_until_negative(&[-5, 10]), 0);
+    }
+
+    #[test]
+    fn test_sum_until_negative_without_negatives() {
+        assert_eq!(sum_until_negative(&[1, 2, 3]), 6);
+        assert_eq!(sum_until_negative(&[]), 0);
+    }
+
+    #[test]
+    fn test_points_round_trip() {
+        let points = vec![Point { x: 1.0, y: 2.0 }, Point { x: -3.5, y: 4.25 }];
+        let coords = flatten_points(&points);
+        assert_eq!(coords, vec![1.0, 2.0, -3.5, 4.25]);
+        assert_eq!(unflatten_points(&coords), Ok(points));
+    }
+
+    #[test]
+    fn test_unflatten_points_odd_length() {
+        assert!(unflatten_points(&[1.0, 2.0, 3.0]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_multiplex_collects_from_both_channels() {
+        let (tx_a, rx_a) = tokio::sync::mpsc::channel(8);
+        let (tx_b, rx_b) = tokio::sync::mpsc::channel(8);
+        for value in [1, 2, 3] {
+            tx_a.send(value).await.unwrap();
+        }
+        for value in [10, 20] {
+            tx_b.send(value).await.unwrap();
+        }
+        drop(tx_a);
+        drop(tx_b);
+
+        let mut received = multiplex(rx_a, rx_b).await;
+        received.sort();
+        assert_eq!(received, vec![1, 2, 3, 10, 20]);
+    }
+
+    #[test]
+    fn test_histogram_typical() {
+        let values = [0.1, 0.2, 1.5, 2.5, 2.6, 3.9];
+        assert_eq!(histogram(&values, 4, 0.0, 4.0), vec![2, 1, 2, 1]);
+    }
+
+    #[test]
+    fn test_histogram_boundaries_and_clamping() {
+        // Interior edges belong to the upper bin; min and max clamp to the edge bins.
+        let values = [0.0, 1.0, 2.0, 3.0, 4.0, -10.0, 99.0];
+        assert_eq!(histogram(&values, 4, 0.0, 4.0), vec![2, 1, 1, 3]);
+        assert!(histogram(&values, 0, 0.0, 4.0).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_until_shutdown_completes_without_signal() {
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let shutdown = async {
+            let _ = shutdown_rx.await;
+        };
+        assert_eq!(run_until_shutdown(async { 7 }, shutdown).await, Some(7));
+    }
+
+    #[tokio::test]
+    async fn test_run_until_shutdown_stops_on_signal() {
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let shutdown = async {
+            let _ = shutdown_rx.await;
+        };
+        shutdown_tx.send(()).unwrap();
+
+        let task = async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            "finished"
+        };
+        assert_eq!(run_until_shutdown(task, shutdown).await, None);
+    }
+
+    #[test]
+    fn test_lower_bound_present() {
+        assert_eq!(lower_bound(&[1, 3, 3, 5], &3), 1);
+    }
+
+    #[test]
+    fn test_lower_bound_absent() {
+        assert_eq!(lower_bound(&[1, 3, 5], &4), 2);
+    }
+
+    #[test]
+    fn test_lower_bound_ends() {
+        assert_eq!(lower_bound(&[1, 3, 5], &0), 0);
+        assert_eq!(lower_bound(&[1, 3, 5], &9), 3);
+        assert_eq!(lower_bound(&[], &1), 0);
+    }
+
+    #[test]
+    fn test_run_counter() {
+        assert_eq!(run_counter(5), 5);
+        assert_eq!(run_counter(0), 0);
+    }
+
+    #[test]
+    fn test_shared_concurrent_with() {
+        let shared = Shared::new(Vec::new());
+        let handles: Vec<_> = (0..4)
+            .map(|id| {
+                let shared = shared.clone_handle();
+                thread::spawn(move || {
+                    for i in 0..25 {
+                        shared.with(|items| items.push(id * 100 + i));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(shared.with(|items| items.len()), 100);
+    }
+
+    #[test]
+    fn test_take_while_sum() {
+        assert_eq!(take_while_sum(&[1, 2, 3, 4], 5), vec![1, 2]);
+        assert_eq!(take_while_sum(&[1, 2, 3, 4], 6), vec![1, 2, 3]);
+        assert!(take_while_sum(&[9, 1], 5).is_empty());
+    }
+
+    #[test]
+    fn test_take_while_sum_large_limit() {
+        assert_eq!(take_while_sum(&[1, 2, 3, 4], 100), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_either_map_left() {
+        let value: Either<i32, &str> = Either::Left(2);
+        assert!(value.is_left());
+        assert_eq!(value.map_left(|n| n + 1), Either::Left(3));
+    }
+
+    #[test]
+    fn test_either_map_right() {
+        let value: Either<i32, &str> = Either::Right("abc");
+        assert!(!value.is_left());
+        assert_eq!(value.map_right(str::len), Either::Right(3));
+    }
+
+    #[test]
+    fn test_either_map_wrong_side_is_noop() {
+        let left: Either<i32, i32> = Either::Left(1);
+        assert_eq!(left.map_right(|n| n * 100), Either::Left(1));
+
+        let right: Either<i32, i32> = Either::Right(1);
+        assert_eq!(right.map_left(|n| n * 100), Either::Right(1));
+    }
+
+    #[test]
+    fn test_all_sections_registered_in_order() {
+        let names: Vec<&str> = all_sections().iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Ownership and Borrowing",
+                "Generics and Traits",
+                "Enums and Pattern Matching",
+                "Error Handling",
+                "Iterators and Closures",
+                "Async Programming",
+                "Multithreading with Mutex",
+                "Smart Pointers",
+                "Collections",
+                "Macros",
+                "Command-Line Arguments",
+                "Panic Recovery",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_approx_eq_epsilon_boundary() {
+        assert!(approx_eq(1.0, 1.0 + 0.5e-6, 1e-6));
+        assert!(approx_eq(-2.0, -2.0 - 0.9e-6, 1e-6));
+        assert!(!approx_eq(1.0, 1.0 + 2e-6, 1e-6));
+        assert!(!approx_eq(0.1 + 0.2, 0.3, 0.0));
+    }
+
+    #[test]
+    fn test_tree_dfs_order() {
+        let tree = TreeNode::new(
+            1,
+            vec![
+                TreeNode::new(2, vec![TreeNode::new(4, vec![]), TreeNode::new(5, vec![])]),
+                TreeNode::new(3, vec![TreeNode::new(6, vec![])]),
+            ],
+        );
+
+        let mut visited = Vec::new();
+        tree.dfs(&mut |value| visited.push(*value));
+        assert_eq!(visited, vec![1, 2, 4, 5, 3, 6]);
+    }
+
+    #[test]
+    fn test_report_shapes_into_buffer() {
+        let shapes: Vec<Box<dyn Shape>> = vec![Box::new(Rectangle {
+            width: 2.0,
+            height: 3.0,
+        })];
+        let mut buffer = Vec::new();
+        report_shapes(&mut buffer, &shapes).unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "Shape 0: area 6.00, perimeter 10.00\n\
+             Shape areas: total 6.00, min 6.00, max 6.00\n"
+        );
+    }
+
+    #[test]
+    fn test_report_table_into_buffer() {
+        let rows = vec![(String::from("Key1"), 100), (String::from("K2"), 7)];
+        let mut buffer = Vec::new();
+        report_table(&mut buffer, &rows).unwrap();
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), "Key1 : 100\nK2   : 7\n");
+    }
+
+    #[test]
+    fn test_flatten_results_separates_oks_and_errs() {
+        let results: Vec<Result<i32, &str>> = vec![Ok(1), Err("bad"), Ok(3), Err("worse")];
+        let (oks, errs) = flatten_results(results);
+        assert_eq!(oks, vec![1, 3]);
+        assert_eq!(errs, vec!["bad", "worse"]);
+    }
+
+    #[test]
+    fn test_rolling_median_odd_window() {
+        assert_eq!(
+            rolling_median(&[5.0, 1.0, 4.0, 2.0, 8.0], 3),
+            vec![4.0, 2.0, 4.0]
+        );
+    }
+
+    #[test]
+    fn test_rolling_median_even_window() {
+        assert_eq!(
+            rolling_median(&[1.0, 3.0, 2.0, 10.0], 2),
+            vec![2.0, 2.5, 6.0]
+        );
+    }
+
+    #[test]
+    fn test_rolling_median_zero_window() {
+        assert!(rolling_median(&[1.0, 2.0], 0).is_empty());
+        assert!(rolling_median(&[1.0, 2.0], 3).is_empty());
+    }
+
+    #[test]
+    fn test_graph_bfs_order() {
+        let mut graph = Graph::new();
+        for (from, to) in [(1, 2), (1, 3), (2, 4), (3, 4), (4, 5), (5, 1)] {
+            graph.add_edge(from, to);
+        }
+        assert_eq!(graph.bfs(1), vec![1, 2, 3, 4, 5]);
+        assert_eq!(graph.bfs(4), vec![4, 5, 1, 2, 3]);
+        assert_eq!(graph.bfs(9), vec![9]);
+    }
+
+    #[test]
+    fn test_dedup_by_key_keeps_first_seen() {
+        let records = [(1, "a"), (2, "b"), (1, "c"), (3, "d"), (2, "e")];
+        assert_eq!(
+            dedup_by_key(&records, |&(id, _)| id),
+            vec![(1, "a"), (2, "b"), (3, "d")]
+        );
+        assert_eq!(
+            dedup_by_key(&["apple", "avocado", "banana"], |s| s.len()),
+            vec!["apple", "avocado", "banana"]
+        );
+        assert_eq!(
+            dedup_by_key(&["apple", "avocado", "banana"], |s| s.chars().next()),
+            vec!["apple", "banana"]
+        );
+    }
+
+    #[test]
+    fn test_now_millis_increases() {
+        let before = now_millis();
+        thread::sleep(Duration::from_millis(5));
+        assert!(now_millis() > before);
+    }
+
+    #[test]
+    fn test_join_display_multiple_items() {
+        assert_eq!(join_display(&[2, 4, 6, 8], ", "), "2, 4, 6, 8");
+        assert_eq!(join_display(&["a", "b"], "-"), "a-b");
+    }
+
+    #[test]
+    fn test_join_display_single_and_empty() {
+        assert_eq!(join_display(&[1.5], ", "), "1.5");
+        assert_eq!(join_display::<i32>(&[], ", "), "");
+    }
+
+    #[tokio::test]
+    async fn test_retry_jitter_is_deterministic_for_seed() {
+        let run = || async {
+            let mut rng = XorShift::new(7);
+            let mut calls = 0;
+            retry_jitter(
+                5,
+                Duration::from_millis(1),
+                Duration::from_millis(5),
+                &mut rng,
+                || {
+                    calls += 1;
+                    let call = calls;
+                    async move {
+                        if call < 3 {
+                            Err(call)
+                        } else {
+                            Ok(call)
+                        }
+                    }
+                },
+            )
+            .await
+        };
+        let (result, waits) = run().await;
+        assert_eq!(result, Ok(3));
+        assert_eq!(waits.len(), 2);
+
+        let mut rng = XorShift::new(7);
+        let expected: Vec<Duration> = (0..2)
+            .map(|i| Duration::from_millis((1 << i) + rng.next_u64() % 5))
+            .collect();
+        assert_eq!(waits, expected);
+        assert_eq!(run().await, (Ok(3), expected));
+    }
+
+    #[tokio::test]
+    async fn test_retry_jitter_gives_up() {
+        let mut rng = XorShift::new(1);
+        let (result, waits): (Result<(), &str>, _) = retry_jitter(
+            3,
+            Duration::from_millis(1),
+            Duration::ZERO,
+            &mut rng,
+            || async { Err("down") },
+        )
+        .await;
+        assert_eq!(result, Err("down"));
+        assert_eq!(
+            waits,
+            vec![Duration::from_millis(1), Duration::from_millis(2)]
+        );
+    }
+
+    #[test]
+    fn test_zip3_equal_lengths() {
+        assert_eq!(
+            zip3(&[1, 2], &["a", "b"], &[true, false]),
+            vec![(1, "a", true), (2, "b", false)]
+        );
+    }
+
+    #[test]
+    fn test_zip3_truncates_to_shortest() {
+        assert_eq!(
+            zip3(&[1, 2, 3], &[4, 5], &[6, 7, 8, 9]),
+            vec![(1, 4, 6), (2, 5, 7)]
+        );
+        assert!(zip3(&[1], &[2], &[] as &[i32]).is_empty());
+    }
+
+    #[test]
+    fn test_perimeter_trait_for_each_shape() {
+        let circle = Circle { radius: 2.0 };
+        assert!(approx_eq(Perimeter::perimeter(&circle), 4.0 * PI, 1e-9));
+        assert!(approx_eq(
+            circle.circumference(),
+            Shape::perimeter(&circle),
+            1e-9
+        ));
+        let rectangle = Rectangle {
+            width: 4.0,
+            height: 3.0,
+        };
+        assert!(approx_eq(Perimeter::perimeter(&rectangle), 14.0, 1e-9));
+    }
+
+    #[test]
+    fn test_total_perimeter() {
+        let rectangles = [
+            Rectangle {
+                width: 1.0,
+                height: 2.0,
+            },
+            Rectangle {
+                width: 3.0,
+                height: 3.0,
+            },
+        ];
+        assert!(approx_eq(total_perimeter(&rectangles), 18.0, 1e-9));
+        let circles = [Circle { radius: 1.0 }, Circle { radius: 0.5 }];
+        assert!(approx_eq(total_perimeter(&circles), 3.0 * PI, 1e-9));
+        assert_eq!(total_perimeter::<Circle>(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_dispatch_commands() {
+        assert_eq!(dispatch("echo hi  there"), Ok(String::from("hi there")));
+        assert_eq!(dispatch("sum 1 2 3"), Ok(String::from("6")));
+        assert!(dispatch("sum 1 x").is_err());
+        assert_eq!(
+            dispatch("sum 9223372036854775807 1"),
+            Err(String::from("sum overflows i64"))
+        );
+        assert_eq!(
+            dispatch("frobnicate"),
+            Err(String::from("unknown command: frobnicate"))
+        );
+    }
+
+    #[test]
+    fn test_run_repl_scripted_session() {
+        let script = "echo hello\nsum 4 5\n\nbogus\nquit\necho never\n";
+        let mut out = Vec::new();
+        run_repl(script.as_bytes(), &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "hello\n9\nerror: unknown command: bogus\nbye\n"
+        );
+    }
+
+    #[test]
+    fn test_moving_sum_matches_brute_force() {
+        let values = [5, -2, 7, 3, 0, 11, -6, 4];
+        for capacity in 1..=4 {
+            let mut moving = MovingSum::new(capacity);
+            for (i, &value) in values.iter().enumerate() {
+                moving.push(value);
+                let start = (i + 1).saturating_sub(capacity);
+                let expected: i64 = values[start..=i].iter().sum();
+                assert_eq!(moving.current(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_moving_sum_zero_capacity() {
+        let mut moving = MovingSum::new(0);
+        moving.push(10);
+        assert_eq!(moving.current(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity of at least 1")]
+    fn test_blocking_queue_rejects_zero_capacity() {
+        BlockingQueue::<i32>::new(0);
+    }
+
+    #[test]
+    fn test_sum_cli_numbers_reports_overflow() {
+        let args = vec![i64::MAX.to_string(), String::from("1")];
+        assert_eq!(sum_cli_numbers(&args), Err(SumError::Overflow));
+        let args = vec![i64::MIN.to_string(), String::from("-1")];
+        assert_eq!(sum_cli_numbers(&args), Err(SumError::Overflow));
+    }
+
+    #[test]
+    fn test_spin_lock_released_after_panic() {
+        let spin_lock = SpinLock::new();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            spin_lock.with_lock(|| panic!("holder panicked"))
+        }));
+        assert!(result.is_err());
+        assert_eq!(spin_lock.with_lock(|| 7), 7);
+    }
+
+    #[test]
+    fn test_histogram_skips_nan() {
+        assert_eq!(
+            histogram(&[f64::NAN, 0.5, f64::NAN, 2.5], 3, 0.0, 3.0),
+            vec![1, 0, 1]
+        );
+        assert_eq!(histogram(&[f64::NAN], 2, 0.0, 1.0), vec![0, 0]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_dir_does_not_follow_symlink_cycle() {
+        let root = env::temp_dir().join("rust_demo_test_walk_symlink");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("real.txt"), "1").unwrap();
+        std::os::unix::fs::symlink(&root, root.join("sub").join("loop")).unwrap();
+
+        let files = walk_dir(root.to_str().unwrap()).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let real = root.join("real.txt").to_string_lossy().into_owned();
+        let link = root.join("sub").join("loop").to_string_lossy().into_owned();
+        assert_eq!(files.iter().filter(|f| **f == real).count(), 1);
+        assert_eq!(files, vec![real, link]);
+    }
+
+    #[test]
+    fn test_shutdown_stops_remaining_sections() {
+        static SHUTDOWN: OnceLock<tokio::sync::watch::Sender<bool>> = OnceLock::new();
+        fn interrupted() -> SectionResult {
+            SHUTDOWN.get().unwrap().send_replace(true);
+            SectionResult::failed("Interrupted", "interrupted by Ctrl-C")
+        }
+        fn unreachable_section() -> SectionResult {
+            SectionResult::ok("After", "should not run")
+        }
+
+        let (tx, rx) = tokio::sync::watch::channel(false);
+        let _ = SHUTDOWN.set(tx);
+        let sections: Vec<Section> =
+            vec![("Interrupted", interrupted), ("After", unreachable_section)];
+        let results = run_sections(sections, &rx);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "Interrupted");
+    }
+
+    #[test]
+    fn test_run_sections_runs_all_without_shutdown() {
+        let (_tx, rx) = tokio::sync::watch::channel(false);
+        let sections: Vec<Section> = vec![
+            ("Macros", macros_demo),
+            ("Panic Recovery", panic_recovery_demo),
+        ];
+        let results = run_sections(sections, &rx);
+        assert!(results.iter().all(|result| result.success));
+        assert_eq!(results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_shutdown_follows_watch() {
+        let (tx, rx) = tokio::sync::watch::channel(false);
+        let task = tokio::time::sleep(Duration::from_secs(5));
+        tx.send_replace(true);
+        assert_eq!(run_until_shutdown(task, wait_for_shutdown(rx)).await, None);
+
+        let (tx, rx) = tokio::sync::watch::channel(false);
+        drop(tx);
+        assert_eq!(
+            run_until_shutdown(async { 3 }, wait_for_shutdown(rx)).await,
+            Some(3)
+        );
     }
 }